@@ -0,0 +1,75 @@
+use std::fs;
+
+/// Options for recording frames as an asciicast v2 file, which terminal
+/// players such as asciinema can play back.
+pub struct CastOptions {
+    pub path: &'static str,
+    /// Seconds between two recorded frames.
+    pub delay: f64,
+    /// Number of frames dropped after each recorded one.
+    pub skip: usize,
+}
+
+pub struct Cast<'a> {
+    options: &'a CastOptions,
+    header: String,
+    events: Vec<String>,
+    num_frames: usize,
+}
+
+impl<'a> Cast<'a> {
+    pub fn new(options: &'a CastOptions, width: usize, height: usize) -> Self {
+        Self {
+            options,
+            header: format!(
+                "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+                width, height
+            ),
+            events: Vec::new(),
+            num_frames: 0,
+        }
+    }
+
+    /// Records `frame`, a block of lines, in place of the previous one.
+    pub fn add_frame(&mut self, frame: &str) {
+        if self.num_frames.is_multiple_of(self.options.skip + 1) {
+            let time = self.events.len() as f64 * self.options.delay;
+            let output = String::from("\x1b[H\x1b[2J")
+                + &frame.trim_end().replace('\n', "\r\n");
+
+            self.events.push(format!(
+                "[{:.3}, \"o\", {}]",
+                time,
+                json_string(&output)
+            ));
+        }
+
+        self.num_frames += 1;
+    }
+
+    pub fn write(&self) {
+        let cast = self
+            .events
+            .iter()
+            .fold(self.header.clone() + "\n", |cast, event| {
+                cast + event + "\n"
+            });
+
+        fs::write(self.options.path, cast).expect("cannot write file");
+    }
+}
+
+fn json_string(src: &str) -> String {
+    let mut json = String::from("\"");
+
+    src.chars().for_each(|c| match c {
+        '"' => json.push_str("\\\""),
+        '\\' => json.push_str("\\\\"),
+        '\n' => json.push_str("\\n"),
+        '\r' => json.push_str("\\r"),
+        c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+        c => json.push(c),
+    });
+
+    json + "\""
+}
@@ -0,0 +1,236 @@
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    Up = 0,
+    Right = 1,
+    Down = 2,
+    Left = 3,
+}
+
+impl Direction {
+    pub fn add(&self, &(x, y): &(usize, usize)) -> (usize, usize) {
+        match self {
+            Direction::Up => (x, y.wrapping_sub(1)),
+            Direction::Right => (x.wrapping_add(1), y),
+            Direction::Down => (x, y.wrapping_add(1)),
+            Direction::Left => (x.wrapping_sub(1), y),
+        }
+    }
+
+    pub fn next(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    fn previous(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn from_guard(cell: u8) -> Option<Direction> {
+        match cell {
+            b'^' => Some(Direction::Up),
+            b'>' => Some(Direction::Right),
+            b'v' => Some(Direction::Down),
+            b'<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn guard(&self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub enum Turn {
+    Right,
+    Left,
+}
+
+pub struct GuardRoute {
+    pub visited: HashSet<(usize, usize)>,
+    pub is_loop: bool,
+}
+
+/// A guard that takes the next turn of `turns` at each obstacle, starting
+/// over at the end.
+struct Guard<'t> {
+    position: (usize, usize),
+    direction: Direction,
+    turns: &'t [Turn],
+    num_turns: usize,
+}
+
+impl<'t> Guard<'t> {
+    fn new(
+        position: (usize, usize),
+        direction: Direction,
+        turns: &'t [Turn],
+    ) -> Self {
+        Self {
+            position,
+            direction,
+            turns,
+            num_turns: 0,
+        }
+    }
+
+    /// The guard is in a loop once this repeats.
+    fn state(&self) -> ((usize, usize), Direction, usize) {
+        (
+            self.position,
+            self.direction,
+            self.num_turns % self.turns.len(),
+        )
+    }
+
+    /// Turns in front of an obstacle or the added `obstruction`, or moves one
+    /// cell forward. Returns whether the guard turned, or `None` once it
+    /// leaves the map.
+    fn step<'a>(
+        &mut self,
+        get_at_coords: &impl Fn((usize, usize)) -> Option<&'a u8>,
+        obstruction: Option<(usize, usize)>,
+    ) -> Option<bool> {
+        let next_position = self.direction.add(&self.position);
+
+        match get_at_coords(next_position) {
+            Some(b'#') => (),
+            Some(_) if Some(next_position) == obstruction => (),
+            Some(_) => {
+                self.position = next_position;
+                return Some(false);
+            }
+            None => return None,
+        }
+
+        self.direction = match self.turns[self.num_turns % self.turns.len()] {
+            Turn::Right => self.direction.next(),
+            Turn::Left => self.direction.previous(),
+        };
+        self.num_turns += 1;
+
+        Some(true)
+    }
+}
+
+/// Walks a guard until it leaves the map or repeats itself. At each obstacle
+/// it takes the next turn of `turns`, starting over at the end.
+pub fn walk_guard<'a>(
+    position_init: (usize, usize),
+    direction_init: Direction,
+    turns: &[Turn],
+    get_at_coords: &impl Fn((usize, usize)) -> Option<&'a u8>,
+) -> GuardRoute {
+    let mut visited = HashSet::new();
+    let mut states = HashSet::new();
+    let mut guard = Guard::new(position_init, direction_init, turns);
+
+    let is_loop = loop {
+        visited.insert(guard.position);
+
+        if !states.insert(guard.state()) {
+            break true;
+        }
+
+        if guard.step(get_at_coords, None).is_none() {
+            break false;
+        }
+    };
+
+    GuardRoute { visited, is_loop }
+}
+
+pub fn get_at_coords_builder<'a>(
+    bytes: &'a [u8],
+    num_cols: usize,
+) -> impl Fn((usize, usize)) -> Option<&'a u8> {
+    let cols_range = 0..num_cols;
+
+    move |(x, y)| {
+        // Rows range is checked by the bytes bounds.
+        if cols_range.contains(&x) {
+            y.checked_mul(num_cols + 1)
+                .and_then(|y| x.checked_add(y))
+                .and_then(|index| bytes.get(index))
+        } else {
+            None
+        }
+    }
+}
+
+/// Prints the guard's route as in the puzzle text: `|` and `-` for vertical
+/// and horizontal moves, `+` where the route turns or crosses itself, and `O`
+/// for the added `obstruction`. Returns whether the guard ends up in a loop.
+pub fn print_route<'a>(
+    position_init: (usize, usize),
+    direction_init: Direction,
+    turns: &[Turn],
+    get_at_coords: &impl Fn((usize, usize)) -> Option<&'a u8>,
+    obstruction: Option<(usize, usize)>,
+) -> bool {
+    const VERTICAL: u8 = 1;
+    const HORIZONTAL: u8 = 2;
+
+    let mut route: HashMap<(usize, usize), u8> = HashMap::new();
+    let mut states = HashSet::new();
+    let mut guard = Guard::new(position_init, direction_init, turns);
+
+    let is_loop = loop {
+        if !states.insert(guard.state()) {
+            break true;
+        }
+
+        let axis = match guard.direction {
+            Direction::Up | Direction::Down => VERTICAL,
+            Direction::Right | Direction::Left => HORIZONTAL,
+        };
+
+        *route.entry(guard.position).or_default() |= axis;
+
+        match guard.step(get_at_coords, obstruction) {
+            Some(true) => {
+                *route.entry(guard.position).or_default() |=
+                    VERTICAL | HORIZONTAL;
+            }
+            Some(false) => (),
+            None => break false,
+        }
+    };
+
+    (0..)
+        .map_while(|y| get_at_coords((0, y)).map(|_| y))
+        .for_each(|y| {
+            let row: String = (0..)
+                .map_while(|x| get_at_coords((x, y)).map(|&cell| (x, cell)))
+                .map(|(x, cell)| match (cell, route.get(&(x, y))) {
+                    (b'#', _) => '#',
+                    _ if Some((x, y)) == obstruction => 'O',
+                    _ if (x, y) == position_init => direction_init.guard(),
+                    (_, Some(&VERTICAL)) => '|',
+                    (_, Some(&HORIZONTAL)) => '-',
+                    (_, Some(_)) => '+',
+                    _ => '.',
+                })
+                .collect();
+
+            println!("{}", row);
+        });
+
+    is_loop
+}
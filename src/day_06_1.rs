@@ -1,79 +1,95 @@
-use std::{collections::HashSet, fs};
+use std::{collections::HashMap, fs};
 
-enum Direction {
-    Up = 0,
-    Right = 1,
-    Down = 2,
-    Left = 3,
-}
-
-impl Direction {
-    fn add(&self, &(x, y): &(usize, usize)) -> (usize, usize) {
-        match self {
-            Direction::Up => (x, y.wrapping_sub(1)),
-            Direction::Right => (x.wrapping_add(1), y),
-            Direction::Down => (x, y.wrapping_add(1)),
-            Direction::Left => (x.wrapping_sub(1), y),
-        }
-    }
-
-    fn next(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
-}
+use crate::day_06::{
+    get_at_coords_builder, print_route, walk_guard, Direction, Turn,
+};
 
-fn get_at_coords_builder<'a>(
-    bytes: &'a [u8],
-    num_cols: usize,
-) -> impl Fn((usize, usize)) -> Option<&'a u8> {
-    let cols_range = 0..num_cols;
-
-    move |(x, y)| {
-        // Rows range is checked by the bytes bounds.
-        if cols_range.contains(&x) {
-            y.checked_mul(num_cols + 1)
-                .and_then(|y| x.checked_add(y))
-                .and_then(|index| bytes.get(index))
-        } else {
-            None
-        }
-    }
-}
+const SHOW_ROUTE: bool = false;
+/// Walks every `^`, `>`, `v` and `<` guard instead of the first `^` one.
+const ALL_GUARDS: bool = false;
+/// Turns taken at each obstacle, repeated in order.
+const TURNS: &[Turn] = &[Turn::Right];
 
 /// https://adventofcode.com/2024/day/6#part1
 pub fn day_06_1() {
     let data = fs::read_to_string("data/day_06.txt").expect("missing file");
     let bytes = data.as_bytes();
 
-    let mut positions = HashSet::new();
-
     if let Some(num_cols) = data.find('\n') {
-        if let Some(location) = data.find('^') {
-            let get_at_coords = get_at_coords_builder(bytes, num_cols);
+        let get_at_coords = get_at_coords_builder(bytes, num_cols);
+        let cols_offset = num_cols + 1;
 
-            let cols_offset = num_cols + 1;
-            let mut position = (location % cols_offset, location / cols_offset);
-            let mut direction = Direction::Up;
+        let mut guards: Vec<_> = bytes
+            .iter()
+            .enumerate()
+            .filter_map(|(location, &cell)| {
+                Direction::from_guard(cell).map(|direction| {
+                    (
+                        (location % cols_offset, location / cols_offset),
+                        direction,
+                    )
+                })
+            })
+            .filter(|(_, direction)| ALL_GUARDS || *direction == Direction::Up)
+            .collect();
 
-            loop {
-                positions.insert(position);
+        if !ALL_GUARDS {
+            guards.truncate(1);
+        }
 
-                let next_position = direction.add(&position);
-                let next_cell = get_at_coords(next_position);
+        let routes: Vec<_> = guards
+            .iter()
+            .map(|&(position, direction)| {
+                walk_guard(position, direction, TURNS, &get_at_coords)
+            })
+            .collect();
 
-                match next_cell {
-                    Some(b'#') => direction = direction.next(),
-                    Some(_) => position = next_position,
-                    None => break,
-                }
+        if SHOW_ROUTE {
+            if let Some(&(position, direction)) = guards.first() {
+                print_route(position, direction, TURNS, &get_at_coords, None);
             }
         }
-    }
 
-    println!("{}", positions.len());
+        if !ALL_GUARDS {
+            if let Some(route) = routes.first() {
+                println!("{}", route.visited.len());
+            }
+
+            return;
+        }
+
+        guards.iter().zip(&routes).for_each(
+            |(&(position, direction), route)| {
+                println!(
+                    "guard {} at {},{}: {} cells, {}",
+                    direction.guard(),
+                    position.0,
+                    position.1,
+                    route.visited.len(),
+                    if route.is_loop { "loops" } else { "escapes" }
+                );
+            },
+        );
+
+        let mut num_guards: HashMap<(usize, usize), usize> = HashMap::new();
+
+        routes
+            .iter()
+            .flat_map(|route| &route.visited)
+            .for_each(|&cell| {
+                *num_guards.entry(cell).or_default() += 1;
+            });
+
+        let mut crossings: Vec<_> = num_guards
+            .into_iter()
+            .filter(|&(_, count)| count > 1)
+            .collect();
+
+        crossings.sort_unstable_by_key(|&((x, y), _)| (y, x));
+
+        println!("{} cells on more than one route", crossings.len());
+        crossings.iter().for_each(|((x, y), count)| {
+            println!("{},{}: {} guards", x, y, count);
+        });
+    }
 }
@@ -1,105 +1,146 @@
-use std::{collections::HashSet, fs};
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-enum Direction {
-    Up = 0,
-    Right = 1,
-    Down = 2,
-    Left = 3,
-}
+use std::{collections::HashSet, fs, thread};
 
-impl Direction {
-    fn add(&self, &(x, y): &(usize, usize)) -> (usize, usize) {
-        match self {
-            Direction::Up => (x, y.wrapping_sub(1)),
-            Direction::Right => (x.wrapping_add(1), y),
-            Direction::Down => (x, y.wrapping_add(1)),
-            Direction::Left => (x.wrapping_sub(1), y),
-        }
-    }
+use crate::day_06::{
+    get_at_coords_builder, print_route, walk_guard, Direction, Turn,
+};
 
-    fn next(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-        }
-    }
-}
+const LIST_LOOPS: bool = false;
+/// Draws the loop created by an obstruction at this position.
+const SHOW_LOOP: Option<(usize, usize)> = None;
 
-fn get_at_coords_builder<'a>(
-    bytes: &'a [u8],
+/// For each cell and direction, where the guard stops when walking that way:
+/// the cell in front of the next obstruction, or `None` if it leaves the map.
+struct JumpTable {
     num_cols: usize,
-) -> impl Fn((usize, usize)) -> Option<&'a u8> {
-    let cols_range = 0..num_cols;
-
-    move |(x, y)| {
-        // Rows range is checked by the bytes bounds.
-        if cols_range.contains(&x) {
-            y.checked_mul(num_cols + 1)
-                .and_then(|y| x.checked_add(y))
-                .and_then(|index| bytes.get(index))
-        } else {
-            None
-        }
-    }
+    stops: Vec<[Option<(usize, usize)>; 4]>,
 }
 
-fn get_initial_path<'a>(
-    position_init: (usize, usize),
-    get_at_coords: &impl Fn((usize, usize)) -> Option<&'a u8>,
-) -> HashSet<(usize, usize)> {
-    let mut positions = HashSet::new();
-    let mut position = position_init;
-    let mut direction = Direction::Up;
-
-    loop {
-        positions.insert(position);
+impl JumpTable {
+    fn new<'a>(
+        num_cols: usize,
+        num_rows: usize,
+        get_at_coords: &impl Fn((usize, usize)) -> Option<&'a u8>,
+    ) -> Self {
+        let mut stops = vec![[None; 4]; num_cols * num_rows];
+
+        let directions = [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ];
+
+        directions.into_iter().for_each(|direction| {
+            // Visits the cells so that the one ahead is always done first.
+            let ys: Vec<_> = match direction {
+                Direction::Down => (0..num_rows).rev().collect(),
+                _ => (0..num_rows).collect(),
+            };
+            let xs: Vec<_> = match direction {
+                Direction::Right => (0..num_cols).rev().collect(),
+                _ => (0..num_cols).collect(),
+            };
+
+            ys.iter().for_each(|&y| {
+                xs.iter().for_each(|&x| {
+                    let ahead = direction.add(&(x, y));
+
+                    stops[y * num_cols + x][direction as usize] =
+                        match get_at_coords(ahead) {
+                            Some(b'#') => Some((x, y)),
+                            // A trailing newline reads as an extra row.
+                            Some(_) if ahead.1 < num_rows => {
+                                stops[ahead.1 * num_cols + ahead.0]
+                                    [direction as usize]
+                            }
+                            _ => None,
+                        };
+                });
+            });
+        });
 
-        let next_position = direction.add(&position);
-        let next_cell = get_at_coords(next_position);
+        Self { num_cols, stops }
+    }
 
-        match next_cell {
-            Some(b'#') => direction = direction.next(),
-            Some(_) => position = next_position,
-            None => break,
+    /// Returns where the guard stops, with an extra obstruction in the map.
+    fn next_stop(
+        &self,
+        (x, y): (usize, usize),
+        direction: Direction,
+        (obstruction_x, obstruction_y): (usize, usize),
+    ) -> Option<(usize, usize)> {
+        let stop = self.stops[y * self.num_cols + x][direction as usize];
+
+        // The obstruction matters if it is ahead and closer than the stop.
+        match direction {
+            Direction::Up
+                if obstruction_x == x
+                    && obstruction_y < y
+                    && stop
+                        .is_none_or(|(_, stop_y)| obstruction_y >= stop_y) =>
+            {
+                Some((x, obstruction_y + 1))
+            }
+            Direction::Right
+                if obstruction_y == y
+                    && obstruction_x > x
+                    && stop
+                        .is_none_or(|(stop_x, _)| obstruction_x <= stop_x) =>
+            {
+                Some((obstruction_x - 1, y))
+            }
+            Direction::Down
+                if obstruction_x == x
+                    && obstruction_y > y
+                    && stop
+                        .is_none_or(|(_, stop_y)| obstruction_y <= stop_y) =>
+            {
+                Some((x, obstruction_y - 1))
+            }
+            Direction::Left
+                if obstruction_y == y
+                    && obstruction_x < x
+                    && stop
+                        .is_none_or(|(stop_x, _)| obstruction_x >= stop_x) =>
+            {
+                Some((obstruction_x + 1, y))
+            }
+            _ => stop,
         }
     }
-
-    positions
 }
 
-fn test_infinite_loop<'a>(
+/// Follows the guard from stop to stop. `hits` holds, per cell and direction,
+/// the `run` in which the guard last turned there, so that it can be reused
+/// across runs without clearing.
+fn test_infinite_loop(
     position_init: (usize, usize),
-    get_at_coords: &impl Fn((usize, usize)) -> Option<&'a u8>,
+    jump_table: &JumpTable,
     position_obstruction: (usize, usize),
+    hits: &mut [usize],
+    run: usize,
 ) -> bool {
-    let mut obstruction_hits = HashSet::new();
     let mut position = position_init;
     let mut direction = Direction::Up;
 
-    loop {
-        let next_position = direction.add(&position);
-        let next_cell = get_at_coords(next_position);
-        let special_obstruction_cell = position_obstruction == next_position;
-
-        match (next_cell, special_obstruction_cell) {
-            (Some(b'#'), _) | (Some(_), true) => {
-                let obstruction_hit = (position, direction);
+    while let Some(stop) =
+        jump_table.next_stop(position, direction, position_obstruction)
+    {
+        let hit =
+            (stop.1 * jump_table.num_cols + stop.0) * 4 + direction as usize;
 
-                // Detect loops: hitting the same obstruction in the same
-                // direction twice.
-                if !obstruction_hits.insert(obstruction_hit) {
-                    return true;
-                }
-
-                direction = direction.next()
-            }
-            (Some(_), false) => position = next_position,
-            (None, _) => return false,
+        // Detect loops: hitting the same obstruction in the same direction
+        // twice.
+        if hits[hit] == run {
+            return true;
         }
+
+        hits[hit] = run;
+        position = stop;
+        direction = direction.next();
     }
+
+    false
 }
 
 /// https://adventofcode.com/2024/day/6#part2
@@ -115,21 +156,93 @@ pub fn day_06_2() {
             let cols_offset = num_cols + 1;
             let position = (location % cols_offset, location / cols_offset);
 
-            let initial_path = get_initial_path(position, &get_at_coords);
+            let num_rows =
+                data.split('\n').take_while(|row| !row.is_empty()).count();
 
-            initial_path
-                .iter()
-                .filter(|&&position_obstruction| {
+            let initial_route = walk_guard(
+                position,
+                Direction::Up,
+                &[Turn::Right],
+                &get_at_coords,
+            );
+
+            if initial_route.is_loop {
+                println!("the guard already loops without an obstruction");
+                return;
+            }
+
+            let jump_table = JumpTable::new(num_cols, num_rows, &get_at_coords);
+
+            let candidates: Vec<_> = initial_route
+                .visited
+                .into_iter()
+                .filter(|&position_obstruction| {
                     position_obstruction != position
-                        && test_infinite_loop(
-                            position,
-                            &get_at_coords,
-                            position_obstruction,
-                        )
                 })
-                .for_each(|position_obstruction| {
-                    loop_possibilities.insert(*position_obstruction);
+                .collect();
+            let num_threads =
+                thread::available_parallelism().map_or(1, |num| num.get());
+            let chunk_size = candidates.len().div_ceil(num_threads).max(1);
+
+            thread::scope(|scope| {
+                let handles: Vec<_> = candidates
+                    .chunks(chunk_size)
+                    .map(|chunk| {
+                        let jump_table = &jump_table;
+
+                        scope.spawn(move || {
+                            let mut hits = vec![0; jump_table.stops.len() * 4];
+
+                            chunk
+                                .iter()
+                                .enumerate()
+                                .filter(|&(run, &position_obstruction)| {
+                                    test_infinite_loop(
+                                        position,
+                                        jump_table,
+                                        position_obstruction,
+                                        &mut hits,
+                                        run + 1,
+                                    )
+                                })
+                                .map(|(_, &position_obstruction)| {
+                                    position_obstruction
+                                })
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+
+                handles.into_iter().for_each(|handle| {
+                    loop_possibilities
+                        .extend(handle.join().expect("thread panicked"));
                 });
+            });
+
+            if LIST_LOOPS {
+                let mut obstructions: Vec<_> =
+                    loop_possibilities.iter().collect();
+
+                obstructions.sort_unstable_by_key(|&&(x, y)| (y, x));
+                obstructions
+                    .iter()
+                    .for_each(|(x, y)| println!("{},{}", x, y));
+            }
+
+            if let Some(obstruction) = SHOW_LOOP {
+                if !print_route(
+                    position,
+                    Direction::Up,
+                    &[Turn::Right],
+                    &get_at_coords,
+                    Some(obstruction),
+                ) {
+                    println!(
+                        "no loop with an obstruction at {:?}",
+                        obstruction
+                    );
+                }
+            }
         }
     }
 
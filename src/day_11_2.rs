@@ -32,7 +32,7 @@ impl StonesCache {
                 self.get_stone_count(1, blink + 1)
             } else {
                 let stone_len = stone.ilog10() + 1;
-                if stone_len % 2 == 0 {
+                if stone_len.is_multiple_of(2) {
                     let stone_half = stone_len / 2;
                     let left = stone % 10u64.pow(stone_half);
                     let right = stone / 10u64.pow(stone_half);
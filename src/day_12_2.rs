@@ -6,6 +6,16 @@ use std::{
 };
 
 const EMPTY: i32 = -1;
+/// Writes the garden as an SVG image: regions coloured by plant, each fence
+/// side drawn as one line, and each region labelled with its area, perimeter,
+/// number of sides and price.
+const SVG: Option<&str> = None;
+/// Size of a garden plot in the SVG image, in pixels.
+const PLOT_SIZE: usize = 32;
+/// Also merges the fences of each region into sides with `PerimeterSet`, which
+/// is quadratic in the number of fences, and reports the regions where it
+/// disagrees with the corner count.
+const CROSS_CHECK: bool = false;
 
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
 struct Coord {
@@ -33,6 +43,17 @@ enum OutDirection {
     Left,
 }
 
+impl OutDirection {
+    fn name(self) -> &'static str {
+        match self {
+            OutDirection::Up => "up",
+            OutDirection::Right => "right",
+            OutDirection::Down => "down",
+            OutDirection::Left => "left",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
 struct Segment {
     p1: Coord,
@@ -58,6 +79,10 @@ impl Segment {
     fn from_coords(p1: Coord, p2: Coord, dir: OutDirection) -> Self {
         Self { p1, p2, dir }
     }
+
+    fn len(&self) -> usize {
+        self.p1.x.abs_diff(self.p2.x) + self.p1.y.abs_diff(self.p2.y)
+    }
 }
 
 impl Debug for Segment {
@@ -185,6 +210,177 @@ fn cell_fences(r: usize, c: usize, regions: &[Vec<i32>]) -> PerimeterSet {
     fences
 }
 
+/// Number of corners of the region at the given plot, which add up to the
+/// number of sides of the region. For each diagonal, the plot has an outer
+/// corner when neither plot beside it along that diagonal is in the region, and
+/// an inner corner when both are but the diagonal plot is not.
+fn cell_corners(r: usize, c: usize, regions: &[Vec<i32>]) -> usize {
+    let region_id = regions[r][c];
+
+    [
+        (r.wrapping_sub(1), c.wrapping_sub(1)),
+        (r.wrapping_sub(1), c.saturating_add(1)),
+        (r.saturating_add(1), c.wrapping_sub(1)),
+        (r.saturating_add(1), c.saturating_add(1)),
+    ]
+    .iter()
+    .filter(|&&(r2, c2)| {
+        let vertical = in_region(r2, c, region_id, regions);
+        let horizontal = in_region(r, c2, region_id, regions);
+        let diagonal = in_region(r2, c2, region_id, regions);
+
+        (!vertical && !horizontal) || (vertical && horizontal && !diagonal)
+    })
+    .count()
+}
+
+/// Hue of a plant, spread around the colour wheel so that neighbouring letters
+/// get different colours.
+fn plant_hue(plant: u8) -> usize {
+    plant as usize * 137 % 360
+}
+
+/// The plot of the region closest to its centre, where its label goes.
+fn label_plot(plots: &[Coord]) -> Option<Coord> {
+    let num_plots = plots.len().max(1) as f64;
+    let (sum_x, sum_y) = plots.iter().fold((0, 0), |(sum_x, sum_y), plot| {
+        (sum_x + plot.x, sum_y + plot.y)
+    });
+    let (centre_x, centre_y) =
+        (sum_x as f64 / num_plots, sum_y as f64 / num_plots);
+
+    plots.iter().copied().min_by(|a, b| {
+        let dist = |plot: &Coord| {
+            (plot.x as f64 - centre_x).powi(2)
+                + (plot.y as f64 - centre_y).powi(2)
+        };
+
+        dist(a).total_cmp(&dist(b))
+    })
+}
+
+fn write_svg(
+    path: &str,
+    map: &[&[u8]],
+    regions: &[Vec<i32>],
+    areas: &HashMap<i32, usize>,
+    perimeters: &HashMap<i32, PerimeterSet>,
+) {
+    // Fences are drawn just inside their region so that the two fences
+    // between neighbouring regions stay apart.
+    const INSET: usize = 3;
+
+    let (width, height) =
+        (regions[0].len() * PLOT_SIZE, regions.len() * PLOT_SIZE);
+    let mut region_ids = areas.keys().copied().collect::<Vec<_>>();
+    region_ids.sort_unstable();
+
+    let mut region_plots: HashMap<i32, Vec<Coord>> = HashMap::new();
+    regions.iter().enumerate().for_each(|(r, row)| {
+        row.iter().enumerate().for_each(|(c, &plot_id)| {
+            region_plots
+                .entry(plot_id)
+                .or_default()
+                .push(Coord::new(c, r));
+        });
+    });
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" \
+        height=\"{}\" font-family=\"sans-serif\" font-size=\"{}\" \
+        text-anchor=\"middle\">\n",
+        width,
+        height,
+        PLOT_SIZE / 4
+    );
+
+    region_ids.iter().for_each(|&region_id| {
+        let plots = region_plots
+            .get(&region_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let Some(label) = label_plot(plots) else {
+            return;
+        };
+        let plant = map[label.y][label.x];
+        let area = areas.get(&region_id).copied().unwrap_or_default();
+        let fences = perimeters.get(&region_id);
+        let perimeter = fences
+            .map(|fences| fences.iter().map(Segment::len).sum::<usize>())
+            .unwrap_or_default();
+        let num_sides = fences.map(PerimeterSet::len).unwrap_or_default();
+
+        svg +=
+            &format!("<g class=\"region\" data-plant=\"{}\">\n", plant as char);
+
+        plots.iter().for_each(|plot| {
+            svg += &format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" \
+                fill=\"hsl({}, 60%, 75%)\"/>\n",
+                plot.x * PLOT_SIZE,
+                plot.y * PLOT_SIZE,
+                PLOT_SIZE,
+                PLOT_SIZE,
+                plant_hue(plant)
+            );
+        });
+
+        fences
+            .iter()
+            .flat_map(|fences| fences.iter())
+            .for_each(|side| {
+                let (x1, y1, x2, y2) = (
+                    side.p1.x * PLOT_SIZE,
+                    side.p1.y * PLOT_SIZE,
+                    side.p2.x * PLOT_SIZE,
+                    side.p2.y * PLOT_SIZE,
+                );
+                let (x1, y1, x2, y2) = match side.dir {
+                    OutDirection::Up => (x1, y1 + INSET, x2, y2 + INSET),
+                    OutDirection::Down => (x1, y1 - INSET, x2, y2 - INSET),
+                    OutDirection::Left => (x1 + INSET, y1, x2 + INSET, y2),
+                    OutDirection::Right => (x1 - INSET, y1, x2 - INSET, y2),
+                };
+
+                svg += &format!(
+                    "<line class=\"side {}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" \
+                y2=\"{}\" stroke=\"hsl({}, 60%, 30%)\" stroke-width=\"2\" \
+                stroke-linecap=\"round\"/>\n",
+                    side.dir.name(),
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    plant_hue(plant)
+                );
+            });
+
+        let (x, y) = (
+            label.x * PLOT_SIZE + PLOT_SIZE / 2,
+            label.y * PLOT_SIZE + PLOT_SIZE / 2,
+        );
+
+        svg += &format!(
+            "<text x=\"{x}\" y=\"{}\">\
+            <tspan x=\"{x}\">{} area {}</tspan>\
+            <tspan x=\"{x}\" dy=\"1.2em\">perimeter {}</tspan>\
+            <tspan x=\"{x}\" dy=\"1.2em\">{} sides</tspan>\
+            <tspan x=\"{x}\" dy=\"1.2em\">price {}</tspan>\
+            </text>\n</g>\n",
+            y - PLOT_SIZE / 4,
+            plant as char,
+            area,
+            perimeter,
+            num_sides,
+            area * num_sides
+        );
+    });
+
+    svg += "</svg>\n";
+
+    fs::write(path, svg).expect("cannot write file");
+}
+
 /// https://adventofcode.com/2024/day/12#part2
 pub fn day_12_2() {
     let data = fs::read_to_string("data/day_12.txt").expect("missing file");
@@ -241,22 +437,61 @@ pub fn day_12_2() {
         });
     });
 
-    let mut perimeters: HashMap<i32, PerimeterSet> = HashMap::new();
+    let mut sides: HashMap<i32, usize> = HashMap::new();
     regions.iter().enumerate().for_each(|(r, row)| {
         row.iter().enumerate().for_each(|(c, &plot_id)| {
-            *perimeters.entry(plot_id).or_default() +=
-                cell_fences(r, c, &regions);
+            *sides.entry(plot_id).or_default() += cell_corners(r, c, &regions);
         });
     });
 
     let price: usize = (0..=last_region_id)
         .map(|region_id| {
-            match (areas.get(&region_id), perimeters.get(&region_id)) {
-                (Some(&area), Some(perimeter)) => area * perimeter.len(),
-                _ => 0,
-            }
+            areas.get(&region_id).copied().unwrap_or_default()
+                * sides.get(&region_id).copied().unwrap_or_default()
         })
         .sum();
 
+    let mut perimeters: HashMap<i32, PerimeterSet> = HashMap::new();
+    if SVG.is_some() || CROSS_CHECK {
+        regions.iter().enumerate().for_each(|(r, row)| {
+            row.iter().enumerate().for_each(|(c, &plot_id)| {
+                *perimeters.entry(plot_id).or_default() +=
+                    cell_fences(r, c, &regions);
+            });
+        });
+    }
+
+    if CROSS_CHECK {
+        let num_mismatches = (0..=last_region_id)
+            .filter(|region_id| {
+                let num_corners =
+                    sides.get(region_id).copied().unwrap_or_default();
+                let num_sides = perimeters
+                    .get(region_id)
+                    .map(PerimeterSet::len)
+                    .unwrap_or_default();
+
+                if num_corners != num_sides {
+                    println!(
+                        "region {}: {} corners but {} merged sides",
+                        region_id, num_corners, num_sides
+                    );
+                }
+
+                num_corners != num_sides
+            })
+            .count();
+
+        println!(
+            "{} of {} regions disagree",
+            num_mismatches,
+            last_region_id + 1
+        );
+    }
+
+    if let Some(path) = SVG {
+        write_svg(path, &map, &regions, &areas, &perimeters);
+    }
+
     println!("{}", price);
 }
@@ -0,0 +1,321 @@
+use std::{
+    fs,
+    io::{stdin, stdout, Write},
+    ops::Range,
+};
+
+pub type Coord = i64;
+
+/// Grey level of a cell with the given number of robots.
+const GREYS: [u8; 4] = [0, 128, 192, 255];
+const SHADES: [char; 4] = [' ', '░', '▒', '█'];
+
+#[derive(Clone, Copy)]
+pub struct Robot {
+    pub position: (Coord, Coord),
+    pub velocity: (Coord, Coord),
+}
+
+fn parse_xy(src: &str) -> Option<(Coord, Coord)> {
+    src.split_once('=').and_then(|(_, xy_src)| {
+        xy_src.split_once(',').and_then(|(x_src, y_src)| {
+            match (x_src.parse::<Coord>(), y_src.parse::<Coord>()) {
+                (Ok(x), Ok(y)) => Some((x, y)),
+                _ => None,
+            }
+        })
+    })
+}
+
+pub struct Lobby {
+    pub width: Coord,
+    pub height: Coord,
+    pub robots: Vec<Robot>,
+}
+
+impl Lobby {
+    pub fn load(width: Coord, height: Coord) -> Self {
+        let data = fs::read_to_string("data/day_14.txt").expect("missing file");
+
+        let robots = data
+            .split('\n')
+            .filter(|line| !line.is_empty())
+            .filter_map(|src| {
+                src.split_once(' ')
+                    .and_then(|(position_src, velocity_src)| {
+                        match (parse_xy(position_src), parse_xy(velocity_src)) {
+                            (Some(position), Some(velocity)) => {
+                                Some(Robot { position, velocity })
+                            }
+                            _ => None,
+                        }
+                    })
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            robots,
+        }
+    }
+
+    /// Robot positions after `t` seconds, which may be negative.
+    pub fn positions_at(
+        &self,
+        t: Coord,
+    ) -> impl Iterator<Item = (Coord, Coord)> + '_ {
+        self.robots.iter().map(move |robot| {
+            let (x, y) = robot.position;
+            let (vx, vy) = robot.velocity;
+
+            (
+                (x + vx * t).rem_euclid(self.width),
+                (y + vy * t).rem_euclid(self.height),
+            )
+        })
+    }
+
+    /// Variance of the robot positions along one axis after `t` seconds,
+    /// scaled by the square of the number of robots to stay an integer.
+    fn scaled_variance(
+        &self,
+        t: Coord,
+        axis: impl Fn((Coord, Coord)) -> Coord,
+    ) -> Coord {
+        let n = self.robots.len() as Coord;
+        let (sum, sum_squares) = self
+            .positions_at(t)
+            .map(axis)
+            .fold((0, 0), |(sum, sum_squares), v| {
+                (sum + v, sum_squares + v * v)
+            });
+
+        n * sum_squares - sum * sum
+    }
+
+    /// Variance of the x and y positions after `t` seconds.
+    pub fn variance(&self, t: Coord) -> (f64, f64) {
+        let n_squared = (self.robots.len() as f64).powi(2);
+
+        (
+            self.scaled_variance(t, |(x, _)| x) as f64 / n_squared,
+            self.scaled_variance(t, |(_, y)| y) as f64 / n_squared,
+        )
+    }
+
+    /// Number of robots in the top left, top right, bottom left and bottom
+    /// right quadrants after `t` seconds. Robots on the middle row or column
+    /// are not in any quadrant.
+    pub fn quadrant_counts(&self, t: Coord) -> [u64; 4] {
+        let (width_half, height_half) = (self.width / 2, self.height / 2);
+        let mut counts = [0; 4];
+
+        self.positions_at(t)
+            .filter(|&(x, y)| {
+                (self.width % 2 == 0 || x != width_half)
+                    && (self.height % 2 == 0 || y != height_half)
+            })
+            .for_each(|(x, y)| {
+                counts[(y >= height_half) as usize * 2
+                    + (x >= width_half) as usize] += 1;
+            });
+
+        counts
+    }
+
+    pub fn safety_factor(&self, t: Coord) -> u64 {
+        self.quadrant_counts(t).iter().product()
+    }
+
+    /// Finds the time when the robots bunch up most on both axes. The x
+    /// positions repeat every `width` steps and the y positions every `height`
+    /// steps, so each axis is searched over one period and the two offsets are
+    /// combined with the Chinese remainder theorem.
+    pub fn min_variance_time(&self) -> Option<Coord> {
+        let t_x = (0..self.width)
+            .min_by_key(|&t| self.scaled_variance(t, |(x, _)| x))?;
+        let t_y = (0..self.height)
+            .min_by_key(|&t| self.scaled_variance(t, |(_, y)| y))?;
+
+        chinese_remainder((t_x, self.width), (t_y, self.height))
+    }
+
+    /// Number of robots on each cell after `t` seconds.
+    pub fn density_at(&self, t: Coord) -> Vec<Vec<usize>> {
+        let mut density =
+            vec![vec![0; self.width as usize]; self.height as usize];
+
+        self.positions_at(t)
+            .for_each(|(x, y)| density[y as usize][x as usize] += 1);
+
+        density
+    }
+}
+
+/// Returns `(a, b)` with `a * x + b * y = gcd(x, y)`, and the gcd.
+fn extended_gcd(x: Coord, y: Coord) -> (Coord, Coord, Coord) {
+    if y == 0 {
+        (1, 0, x)
+    } else {
+        let (a, b, gcd) = extended_gcd(y, x % y);
+
+        (b, a - (x / y) * b, gcd)
+    }
+}
+
+/// Returns the smallest `t >= 0` with `t = r1 (mod m1)` and `t = r2 (mod m2)`.
+fn chinese_remainder(
+    (r1, m1): (Coord, Coord),
+    (r2, m2): (Coord, Coord),
+) -> Option<Coord> {
+    let (a, _, gcd) = extended_gcd(m1, m2);
+
+    if (r2 - r1) % gcd != 0 {
+        return None;
+    }
+
+    let lcm = m1 / gcd * m2;
+    let k = ((r2 - r1) / gcd * a).rem_euclid(m2 / gcd);
+
+    Some((r1 + m1 * k).rem_euclid(lcm))
+}
+
+pub fn print_density(density: &[Vec<usize>]) {
+    density.iter().for_each(|row| {
+        row.iter().for_each(|&count| {
+            let shade = count.min(SHADES.len() - 1);
+
+            // Greys 232 to 255 of the 256 colour palette.
+            print!(
+                "\x1b[38;5;{}m{}",
+                232 + GREYS[shade] as usize * 23 / 255,
+                SHADES[shade]
+            );
+        });
+
+        println!("\x1b[0m");
+    });
+}
+
+/// Steps through the simulation from `t`, reading commands from stdin.
+pub fn view(lobby: &Lobby, mut t: Coord) {
+    loop {
+        print_density(&lobby.density_at(t));
+        println!("t = {}", t);
+        println!("(N)ext, (p)revious, a time to jump to, or (q)uit.");
+        let _ = stdout().flush();
+
+        let mut input = String::new();
+
+        if stdin().read_line(&mut input).is_err() {
+            return;
+        }
+
+        match input.trim().to_lowercase().as_str() {
+            "" | "n" => t += 1,
+            "p" => t -= 1,
+            "q" => return,
+            command => match command.parse::<Coord>() {
+                Ok(time) => t = time,
+                Err(_) => println!("Unknown command {:?}!", command),
+            },
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy)]
+pub enum ImageFormat {
+    /// Greyscale by number of robots per cell.
+    Pgm,
+    /// Robots in green, brighter where several share a cell.
+    Ppm,
+}
+
+fn write_image(
+    path: &str,
+    format: ImageFormat,
+    width: usize,
+    height: usize,
+    greys: &[u8],
+) {
+    let mut image = match format {
+        ImageFormat::Pgm => format!("P5\n{} {}\n255\n", width, height),
+        ImageFormat::Ppm => format!("P6\n{} {}\n255\n", width, height),
+    }
+    .into_bytes();
+
+    match format {
+        ImageFormat::Pgm => image.extend(greys),
+        ImageFormat::Ppm => image
+            .extend(greys.iter().flat_map(|&grey| [grey / 4, grey, grey / 4])),
+    }
+
+    fs::write(path, image).expect("cannot write file");
+}
+
+/// Writes one image per frame, named `<prefix>_<t>.pgm` or `.ppm`.
+pub fn write_frames(
+    lobby: &Lobby,
+    times: Range<Coord>,
+    format: ImageFormat,
+    prefix: &str,
+) {
+    let extension = match format {
+        ImageFormat::Pgm => "pgm",
+        ImageFormat::Ppm => "ppm",
+    };
+
+    times.for_each(|t| {
+        let greys = lobby
+            .density_at(t)
+            .iter()
+            .flatten()
+            .map(|&count| GREYS[count.min(GREYS.len() - 1)])
+            .collect::<Vec<_>>();
+
+        write_image(
+            &format!("{}_{:05}.{}", prefix, t, extension),
+            format,
+            lobby.width as usize,
+            lobby.height as usize,
+            &greys,
+        );
+    });
+}
+
+/// Writes the frames tiled left to right, `columns` per row, with a one pixel
+/// border around each.
+pub fn write_contact_sheet(
+    lobby: &Lobby,
+    times: Range<Coord>,
+    columns: usize,
+    format: ImageFormat,
+    path: &str,
+) {
+    const BORDER: u8 = 64;
+
+    let (frame_width, frame_height) =
+        (lobby.width as usize + 1, lobby.height as usize + 1);
+    let rows = times.clone().count().div_ceil(columns);
+    let (width, height) = (columns * frame_width + 1, rows * frame_height + 1);
+
+    let mut greys = vec![BORDER; width * height];
+
+    times.enumerate().for_each(|(i, t)| {
+        let (left, top) = (
+            (i % columns) * frame_width + 1,
+            (i / columns) * frame_height + 1,
+        );
+
+        lobby.density_at(t).iter().enumerate().for_each(|(y, row)| {
+            row.iter().enumerate().for_each(|(x, &count)| {
+                greys[(top + y) * width + left + x] =
+                    GREYS[count.min(GREYS.len() - 1)];
+            });
+        });
+    });
+
+    write_image(path, format, width, height, &greys);
+}
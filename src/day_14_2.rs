@@ -1,66 +1,82 @@
 use std::{
     collections::{HashMap, HashSet},
-    fs,
-    hash::Hash,
-    str::FromStr,
+    ops::Range,
 };
 
-const MAP_WIDTH: usize = 101;
-const MAP_HEIGHT: usize = 103;
-const EMPTY: isize = -1;
-
-type Coord = usize;
-type Dir = isize;
-
-#[derive(Copy, Clone, Eq, Hash, PartialEq)]
-struct XY<TUnit>
-where
-    TUnit: Copy + Clone + Eq + Hash + PartialEq,
-{
-    x: TUnit,
-    y: TUnit,
-}
+use crate::{
+    asciicast::{Cast, CastOptions},
+    day_14::{
+        view, write_contact_sheet, write_frames, Coord, ImageFormat, Lobby,
+    },
+};
 
-type Position = XY<Coord>;
-type Velocity = XY<Dir>;
-
-fn parse_xy<TUnit>(src: &str) -> Option<XY<TUnit>>
-where
-    TUnit: Copy + Clone + Eq + Hash + PartialEq + FromStr,
-{
-    src.split_once('=').and_then(|(_, xy_src)| {
-        xy_src.split_once(',').and_then(|(x_src, y_src)| {
-            match (x_src.parse::<TUnit>(), y_src.parse::<TUnit>()) {
-                (Ok(x), Ok(y)) => Some(XY { x, y }),
-                _ => None,
-            }
-        })
-    })
+const MAP_WIDTH: Coord = 101;
+const MAP_HEIGHT: Coord = 103;
+/// Size of the grids the robots are drawn and grouped into regions on.
+const NUM_COLS: usize = MAP_WIDTH as usize;
+const NUM_ROWS: usize = MAP_HEIGHT as usize;
+const EMPTY: isize = -1;
+/// Frames to show or export around the tree.
+const VIEW: Option<View> = None;
+/// How to look for the tree.
+const STRATEGY: Strategy = Strategy::Variance;
+/// Records the frames at these times as an asciicast.
+const CAST: Option<(Range<Coord>, CastOptions)> = None;
+
+/// How to detect the Christmas tree.
+#[allow(dead_code)]
+enum Strategy {
+    /// Steps the robots until a few regions hold a quarter of them.
+    Regions,
+    /// Combines the times of least variance on each axis.
+    Variance,
+    /// Runs both and reports whether they agree.
+    CrossCheck,
 }
 
-struct Robot {
-    position: Position,
-    velocity: Velocity,
+/// What to show once the tree is found, or from t = 0 if it is not.
+#[allow(dead_code)]
+enum View {
+    /// Steps through the frames in the terminal, starting at the tree.
+    Interactive,
+    Frames {
+        times: Range<Coord>,
+        format: ImageFormat,
+        prefix: &'static str,
+    },
+    ContactSheet {
+        times: Range<Coord>,
+        columns: usize,
+        format: ImageFormat,
+        path: &'static str,
+    },
 }
 
-fn print_map(cells_with_robots: &HashSet<Position>) {
-    println!("+{}+", "-".repeat(MAP_WIDTH));
+type Position = (usize, usize);
 
-    (0..MAP_HEIGHT).for_each(|y| {
-        print!("|");
+fn map_to_string(cells_with_robots: &HashSet<Position>) -> String {
+    let border = format!("+{}+\n", "-".repeat(NUM_COLS));
 
-        (0..MAP_WIDTH).for_each(|x| {
-            if cells_with_robots.contains(&XY { x, y }) {
-                print!("*");
+    (0..NUM_ROWS).fold(border.clone(), |map, y| {
+        (0..NUM_COLS).fold(map + "|", |map, x| {
+            if cells_with_robots.contains(&(x, y)) {
+                map + "*"
             } else {
-                print!(" ");
+                map + " "
             }
-        });
+        }) + "|\n"
+    }) + &border
+}
 
-        println!("|");
-    });
+fn print_map(cells_with_robots: &HashSet<Position>) {
+    print!("{}", map_to_string(cells_with_robots));
+}
 
-    println!("+{}+", "-".repeat(MAP_WIDTH));
+fn cells_at(lobby: &Lobby, t: Coord) -> HashSet<Position> {
+    lobby
+        .positions_at(t)
+        .map(|(x, y)| (x as usize, y as usize))
+        .collect()
 }
 
 fn map_regions(map: &mut [Vec<isize>], cells_with_robots: &HashSet<Position>) {
@@ -68,9 +84,9 @@ fn map_regions(map: &mut [Vec<isize>], cells_with_robots: &HashSet<Position>) {
 
     let mut last_region_id = -1;
 
-    for y in 0..MAP_HEIGHT {
-        for x in 0..MAP_WIDTH {
-            let cell = XY { x, y };
+    for y in 0..NUM_ROWS {
+        for x in 0..NUM_COLS {
+            let cell = (x, y);
             if map[y][x] == EMPTY && cells_with_robots.contains(&cell) {
                 last_region_id += 1;
 
@@ -78,7 +94,7 @@ fn map_regions(map: &mut [Vec<isize>], cells_with_robots: &HashSet<Position>) {
                 remaining.push(cell);
 
                 while let Some(cell) = remaining.pop() {
-                    let XY { x, y } = cell;
+                    let (x, y) = cell;
 
                     if let Some(EMPTY) =
                         map.get(y).and_then(|row| row.get(x).copied())
@@ -90,38 +106,14 @@ fn map_regions(map: &mut [Vec<isize>], cells_with_robots: &HashSet<Position>) {
 
                             remaining.extend(
                                 [
-                                    XY {
-                                        x: x.wrapping_sub(1),
-                                        y: y.wrapping_sub(1),
-                                    },
-                                    XY {
-                                        x: x.wrapping_sub(1),
-                                        y: y.saturating_add(1),
-                                    },
-                                    XY {
-                                        x: x.saturating_add(1),
-                                        y: y.wrapping_sub(1),
-                                    },
-                                    XY {
-                                        x: x.saturating_add(1),
-                                        y: y.saturating_add(1),
-                                    },
-                                    XY {
-                                        x: x.wrapping_sub(1),
-                                        y,
-                                    },
-                                    XY {
-                                        x,
-                                        y: y.saturating_add(1),
-                                    },
-                                    XY {
-                                        x,
-                                        y: y.wrapping_sub(1),
-                                    },
-                                    XY {
-                                        x: x.saturating_add(1),
-                                        y,
-                                    },
+                                    (x.wrapping_sub(1), y.wrapping_sub(1)),
+                                    (x.wrapping_sub(1), y.saturating_add(1)),
+                                    (x.saturating_add(1), y.wrapping_sub(1)),
+                                    (x.saturating_add(1), y.saturating_add(1)),
+                                    (x.wrapping_sub(1), y),
+                                    (x, y.saturating_add(1)),
+                                    (x, y.wrapping_sub(1)),
+                                    (x.saturating_add(1), y),
                                 ]
                                 .iter(),
                             );
@@ -152,49 +144,73 @@ fn has_christmas_tree(map: &[Vec<isize>], num_robots: usize) -> bool {
     total_in_top_regions > num_robots / 4
 }
 
+fn find_tree_by_regions(lobby: &Lobby) -> Option<Coord> {
+    let mut region_map = vec![vec![EMPTY; NUM_COLS]; NUM_ROWS];
+
+    for i in 1..=1_000_000 {
+        map_regions(&mut region_map, &cells_at(lobby, i));
+
+        if has_christmas_tree(&region_map, lobby.robots.len()) {
+            return Some(i);
+        }
+    }
+
+    None
+}
+
 /// https://adventofcode.com/2024/day/14#part2
 pub fn day_14_2() {
-    let data = fs::read_to_string("data/day_14.txt").expect("missing file");
-
-    let mut robots: Vec<_> = data
-        .split('\n')
-        .filter(|line| !line.is_empty())
-        .filter_map(|src| {
-            src.split_once(' ')
-                .and_then(|(position_src, velocity_src)| {
-                    match (parse_xy(position_src), parse_xy(velocity_src)) {
-                        (Some(position), Some(velocity)) => {
-                            Some(Robot { position, velocity })
-                        }
-                        _ => None,
-                    }
-                })
-        })
-        .collect();
+    let lobby = Lobby::load(MAP_WIDTH, MAP_HEIGHT);
+
+    let tree_time = match STRATEGY {
+        Strategy::Regions => find_tree_by_regions(&lobby),
+        Strategy::Variance => lobby.min_variance_time(),
+        Strategy::CrossCheck => {
+            let by_regions = find_tree_by_regions(&lobby);
+            let by_variance = lobby.min_variance_time();
+
+            if by_regions != by_variance {
+                println!(
+                    "strategies disagree: regions {:?}, variance {:?}",
+                    by_regions, by_variance
+                );
+            }
 
-    let mut region_map = vec![vec![EMPTY; MAP_WIDTH]; MAP_HEIGHT];
+            by_variance
+        }
+    };
 
-    for i in 1..=1_000_000 {
-        robots.iter_mut().for_each(|robot| {
-            let Robot { position, velocity } = robot;
-
-            position.x = (velocity.x.saturating_add_unsigned(position.x))
-                .rem_euclid(MAP_WIDTH as isize)
-                .unsigned_abs();
-            position.y = (velocity.y.saturating_add_unsigned(position.y))
-                .rem_euclid(MAP_HEIGHT as isize)
-                .unsigned_abs();
-        });
+    if let Some(t) = tree_time {
+        print_map(&cells_at(&lobby, t));
+        println!("{}", t);
+    }
+
+    if let Some((times, options)) = &CAST {
+        let mut cast = Cast::new(options, NUM_COLS + 2, NUM_ROWS + 2);
 
-        let cells_with_robots: HashSet<_> =
-            robots.iter().map(|robot| robot.position).collect();
+        times.clone().for_each(|t| {
+            cast.add_frame(&map_to_string(&cells_at(&lobby, t)));
+        });
 
-        map_regions(&mut region_map, &cells_with_robots);
+        cast.write();
+    }
 
-        if has_christmas_tree(&region_map, robots.len()) {
-            print_map(&cells_with_robots);
-            println!("{}", i);
-            break;
+    if let Some(view_mode) = VIEW {
+        let tree_time = tree_time.unwrap_or(0);
+
+        match view_mode {
+            View::Interactive => view(&lobby, tree_time),
+            View::Frames {
+                times,
+                format,
+                prefix,
+            } => write_frames(&lobby, times, format, prefix),
+            View::ContactSheet {
+                times,
+                columns,
+                format,
+                path,
+            } => write_contact_sheet(&lobby, times, columns, format, path),
         }
     }
 }
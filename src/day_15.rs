@@ -0,0 +1,492 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    fs,
+    io::{stdin, stdout, Write},
+};
+
+use crate::asciicast::{Cast, CastOptions};
+
+const SHOW_MAPS: bool = false;
+const REPLAY: bool = false;
+const CAST: Option<CastOptions> = None;
+
+type Movement = (isize, isize);
+type Position = (usize, usize);
+
+/// A cell rewritten by one move.
+struct Change {
+    position: Position,
+    before: u8,
+    after: u8,
+}
+
+/// One entry of the movement list, with what it changed so that it can be
+/// undone.
+struct Step {
+    movement: Movement,
+    changes: Vec<Change>,
+    /// Cells of the boxes it pushed, where they ended up. A pushed box can
+    /// land on a cell that looked the same before, so this is not `changes`.
+    pushed_cells: HashSet<Position>,
+    gps_sum: usize,
+}
+
+#[allow(dead_code)]
+pub enum Layout {
+    /// Every cell of the input becomes `width`×`height` cells, and every `O`
+    /// one box of that size. The robot keeps to the top left cell.
+    Scaled { width: usize, height: usize },
+    /// The input is drawn at full size. `O` is a box of one cell, `[` starts a
+    /// box that ends at the next `]` on its row, and any other letter marks
+    /// the cells of a rectangular box made of that letter.
+    Legend,
+}
+
+struct WarehouseBox {
+    position: Position,
+    width: usize,
+    height: usize,
+}
+
+impl WarehouseBox {
+    fn cells(&self) -> impl Iterator<Item = Position> + '_ {
+        let (x, y) = self.position;
+
+        (y..y + self.height)
+            .flat_map(move |y| (x..x + self.width).map(move |x| (x, y)))
+    }
+
+    fn cell(&self, (x, _): Position) -> u8 {
+        match self.width {
+            1 => b'O',
+            _ if x == self.position.0 => b'[',
+            _ if x == self.position.0 + self.width - 1 => b']',
+            _ => b'-',
+        }
+    }
+}
+
+struct Warehouse {
+    walls: Vec<Vec<bool>>,
+    box_ids: Vec<Vec<Option<usize>>>,
+    boxes: Vec<WarehouseBox>,
+    robot_position: Position,
+    /// Sum of the GPS coordinates of the top left corner of every box.
+    gps_sum: usize,
+}
+
+fn add_move(position: Position, movement: Movement) -> Position {
+    (
+        position.0.saturating_add_signed(movement.0),
+        position.1.saturating_add_signed(movement.1),
+    )
+}
+
+impl Warehouse {
+    fn parse(lines: &[&[u8]], layout: &Layout) -> Option<Self> {
+        let (scale_x, scale_y) = match layout {
+            Layout::Scaled { width, height } => (*width, *height),
+            Layout::Legend => (1, 1),
+        };
+        let max_x = lines.iter().map(|line| line.len()).max()? * scale_x;
+        let max_y = lines.len() * scale_y;
+
+        let mut walls = vec![vec![false; max_x]; max_y];
+        let mut boxes = Vec::new();
+        let mut robot_position = None;
+
+        lines.iter().enumerate().for_each(|(y, line)| {
+            line.iter().enumerate().for_each(|(x, &cell)| {
+                let position = (x * scale_x, y * scale_y);
+
+                match cell {
+                    b'#' => (0..scale_y).for_each(|dy| {
+                        walls[position.1 + dy][position.0..position.0 + scale_x]
+                            .fill(true)
+                    }),
+                    b'@' => robot_position = Some(position),
+                    b'O' => boxes.push(WarehouseBox {
+                        position,
+                        width: scale_x,
+                        height: scale_y,
+                    }),
+                    _ => (),
+                }
+            })
+        });
+
+        if let Layout::Legend = layout {
+            boxes.extend(parse_legend_boxes(lines)?);
+        }
+
+        let mut box_ids = vec![vec![None; max_x]; max_y];
+
+        boxes.iter().enumerate().for_each(|(id, warehouse_box)| {
+            warehouse_box
+                .cells()
+                .for_each(|(x, y)| box_ids[y][x] = Some(id))
+        });
+
+        let gps_sum = boxes
+            .iter()
+            .map(|warehouse_box| {
+                100 * warehouse_box.position.1 + warehouse_box.position.0
+            })
+            .sum();
+
+        Some(Self {
+            walls,
+            box_ids,
+            boxes,
+            robot_position: robot_position?,
+            gps_sum,
+        })
+    }
+
+    fn cell(&self, position: Position) -> u8 {
+        let (x, y) = position;
+
+        if self.walls[y][x] {
+            b'#'
+        } else if self.robot_position == position {
+            b'@'
+        } else if let Some(id) = self.box_ids[y][x] {
+            self.boxes[id].cell(position)
+        } else {
+            b'.'
+        }
+    }
+
+    fn to_map(&self) -> Vec<Vec<u8>> {
+        (0..self.walls.len())
+            .map(|y| {
+                (0..self.walls[y].len())
+                    .map(|x| self.cell((x, y)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Moves the robot and every box it pushes, unless one of them would run
+    /// into a wall. Returns the cells that changed and the cells of the pushed
+    /// boxes after the move.
+    fn move_robot(
+        &mut self,
+        movement: Movement,
+    ) -> (Vec<Change>, HashSet<Position>) {
+        let next_robot_position = add_move(self.robot_position, movement);
+
+        if self.walls[next_robot_position.1][next_robot_position.0] {
+            return (Vec::new(), HashSet::new());
+        }
+
+        let mut pushed_boxes = Vec::new();
+        let mut seen = HashSet::new();
+        let mut remaining = VecDeque::new();
+
+        if let Some(id) =
+            self.box_ids[next_robot_position.1][next_robot_position.0]
+        {
+            seen.insert(id);
+            remaining.push_back(id);
+        }
+
+        while let Some(id) = remaining.pop_front() {
+            pushed_boxes.push(id);
+
+            for (x, y) in self.boxes[id].cells() {
+                let (next_x, next_y) = add_move((x, y), movement);
+
+                if self.walls[next_y][next_x] {
+                    return (Vec::new(), HashSet::new());
+                }
+
+                if let Some(other_id) = self.box_ids[next_y][next_x] {
+                    if seen.insert(other_id) {
+                        remaining.push_back(other_id);
+                    }
+                }
+            }
+        }
+
+        let mut changes: Vec<_> = pushed_boxes
+            .iter()
+            .flat_map(|&id| self.boxes[id].cells())
+            .flat_map(|position| [position, add_move(position, movement)])
+            .chain([self.robot_position, next_robot_position])
+            .map(|position| Change {
+                position,
+                before: self.cell(position),
+                after: b'.',
+            })
+            .collect();
+
+        changes.sort_unstable_by_key(|change| change.position);
+        changes.dedup_by_key(|change| change.position);
+
+        pushed_boxes.iter().for_each(|&id| {
+            self.boxes[id]
+                .cells()
+                .for_each(|(x, y)| self.box_ids[y][x] = None)
+        });
+
+        pushed_boxes.iter().for_each(|&id| {
+            let warehouse_box = &mut self.boxes[id];
+
+            warehouse_box.position = add_move(warehouse_box.position, movement);
+            self.gps_sum = self
+                .gps_sum
+                .saturating_add_signed(100 * movement.1 + movement.0);
+
+            warehouse_box
+                .cells()
+                .for_each(|(x, y)| self.box_ids[y][x] = Some(id));
+        });
+
+        self.robot_position = next_robot_position;
+
+        changes.iter_mut().for_each(|change| {
+            change.after = self.cell(change.position);
+        });
+        changes.retain(|change| change.before != change.after);
+
+        let pushed_cells = pushed_boxes
+            .iter()
+            .flat_map(|&id| self.boxes[id].cells())
+            .collect();
+
+        (changes, pushed_cells)
+    }
+}
+
+/// Finds the boxes drawn with `[`...`]` or with letters other than `O`.
+fn parse_legend_boxes(lines: &[&[u8]]) -> Option<Vec<WarehouseBox>> {
+    let mut boxes = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (y, line) in lines.iter().enumerate() {
+        for (x, &cell) in line.iter().enumerate() {
+            if cell == b'[' {
+                let width = line[x..].iter().position(|&end| end == b']')? + 1;
+
+                boxes.push(WarehouseBox {
+                    position: (x, y),
+                    width,
+                    height: 1,
+                });
+            } else if cell.is_ascii_alphabetic()
+                && cell != b'O'
+                && !seen.contains(&(x, y))
+            {
+                let mut cells = vec![(x, y)];
+                let mut remaining = vec![(x, y)];
+
+                seen.insert((x, y));
+
+                while let Some((x, y)) = remaining.pop() {
+                    [
+                        (x.wrapping_sub(1), y),
+                        (x + 1, y),
+                        (x, y.wrapping_sub(1)),
+                        (x, y + 1),
+                    ]
+                    .into_iter()
+                    .filter(|&(x, y)| {
+                        lines.get(y).and_then(|line| line.get(x)) == Some(&cell)
+                    })
+                    .for_each(|position| {
+                        if seen.insert(position) {
+                            cells.push(position);
+                            remaining.push(position);
+                        }
+                    });
+                }
+
+                let min_x = cells.iter().map(|&(x, _)| x).min()?;
+                let max_x = cells.iter().map(|&(x, _)| x).max()?;
+                let max_y = cells.iter().map(|&(_, y)| y).max()?;
+                let (width, height) = (max_x - min_x + 1, max_y - y + 1);
+
+                // Only rectangles are boxes.
+                if cells.len() != width * height {
+                    return None;
+                }
+
+                boxes.push(WarehouseBox {
+                    position: (min_x, y),
+                    width,
+                    height,
+                });
+            }
+        }
+    }
+
+    Some(boxes)
+}
+
+/// Prints the map with the robot in yellow and the boxes moved by `step` in
+/// green.
+fn print_step(map: &[Vec<u8>], step: Option<&Step>) {
+    map.iter().enumerate().for_each(|(y, row)| {
+        row.iter().enumerate().for_each(|(x, &cell)| {
+            let is_moved =
+                step.is_some_and(|step| step.pushed_cells.contains(&(x, y)));
+
+            match cell {
+                b'@' => print!("\x1b[1;33m@\x1b[0m"),
+                b'O' | b'[' | b'-' | b']' if is_moved => {
+                    print!("\x1b[1;32m{}\x1b[0m", cell as char)
+                }
+                _ => print!("{}", cell as char),
+            }
+        });
+
+        println!();
+    });
+}
+
+/// Steps through the recorded moves, starting from the initial `map`.
+fn replay(mut map: Vec<Vec<u8>>, gps_sum: usize, steps: &[Step]) {
+    let mut num_moves: usize = 0;
+
+    loop {
+        let step = num_moves.checked_sub(1).map(|i| &steps[i]);
+
+        print_step(&map, step);
+        println!(
+            "move {}/{} {}, GPS sum {}",
+            num_moves,
+            steps.len(),
+            match step.map(|step| step.movement) {
+                Some((0, -1)) => "^",
+                Some((1, 0)) => ">",
+                Some((0, 1)) => "v",
+                Some((-1, 0)) => "<",
+                _ => "",
+            },
+            step.map_or(gps_sum, |step| step.gps_sum)
+        );
+        println!("(N)ext, (p)revious, a move to jump to, or (q)uit.");
+        let _ = stdout().flush();
+
+        let mut input = String::new();
+
+        if stdin().read_line(&mut input).is_err() {
+            return;
+        }
+
+        let target = match input.trim().to_lowercase().as_str() {
+            "" | "n" => num_moves + 1,
+            "p" => num_moves.saturating_sub(1),
+            "q" => return,
+            command => match command.parse::<usize>() {
+                Ok(target) => target,
+                Err(_) => {
+                    println!("Unknown command {:?}!", command);
+                    num_moves
+                }
+            },
+        }
+        .min(steps.len());
+
+        while num_moves < target {
+            steps[num_moves].changes.iter().for_each(|change| {
+                map[change.position.1][change.position.0] = change.after;
+            });
+            num_moves += 1;
+        }
+
+        while num_moves > target {
+            num_moves -= 1;
+            steps[num_moves].changes.iter().for_each(|change| {
+                map[change.position.1][change.position.0] = change.before;
+            });
+        }
+    }
+}
+
+fn map_to_string(map: &[Vec<u8>]) -> String {
+    map.iter()
+        .map(|row| String::from_utf8_lossy(row) + "\n")
+        .collect()
+}
+
+fn print_map(map: &[Vec<u8>]) {
+    println!("{}", map_to_string(map));
+}
+
+/// Runs the robot through the warehouse and prints the GPS sum of the boxes.
+pub fn print_gps_sum(layout: &Layout) {
+    let data = fs::read_to_string("data/day_15.txt").expect("missing file");
+    let mut lines = data.split('\n');
+
+    let map_lines: Vec<_> = lines
+        .by_ref()
+        .take_while(|line| !line.is_empty())
+        .map(|line| line.as_bytes())
+        .collect();
+
+    let movements = lines.flat_map(|line| {
+        line.as_bytes()
+            .iter()
+            .filter_map(|movement| match movement {
+                b'^' => Some((0, -1)),
+                b'>' => Some((1, 0)),
+                b'v' => Some((0, 1)),
+                b'<' => Some((-1, 0)),
+                _ => None,
+            })
+    });
+
+    let Some(mut warehouse) = Warehouse::parse(&map_lines, layout) else {
+        println!("invalid warehouse map");
+        return;
+    };
+
+    let initial_map = warehouse.to_map();
+    let initial_gps_sum = warehouse.gps_sum;
+    let mut steps = Vec::new();
+    let mut cast = CAST.as_ref().map(|options| {
+        Cast::new(options, initial_map[0].len(), initial_map.len())
+    });
+
+    movements.for_each(|movement| {
+        if SHOW_MAPS {
+            print_map(&warehouse.to_map());
+        }
+
+        if let Some(cast) = &mut cast {
+            cast.add_frame(&map_to_string(&warehouse.to_map()));
+        }
+
+        let (changes, pushed_cells) = warehouse.move_robot(movement);
+
+        if REPLAY {
+            steps.push(Step {
+                movement,
+                changes,
+                pushed_cells,
+                gps_sum: warehouse.gps_sum,
+            });
+        }
+
+        if SHOW_MAPS {
+            println!("{:?}", movement);
+        }
+    });
+
+    if SHOW_MAPS {
+        print_map(&warehouse.to_map());
+    }
+
+    if let Some(cast) = &mut cast {
+        cast.add_frame(&map_to_string(&warehouse.to_map()));
+        cast.write();
+    }
+
+    if REPLAY {
+        replay(initial_map, initial_gps_sum, &steps);
+    }
+
+    println!("{}", warehouse.gps_sum);
+}
@@ -0,0 +1,619 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt::Debug,
+    fs,
+};
+
+const DEBUG: bool = false;
+/// Draws the best-path tiles as coloured text or as an image.
+const RENDER: Option<Render> = None;
+const SHOW_TURNS: bool = false;
+const PPM_SCALE: usize = 4;
+const SHOW_STATS: bool = false;
+
+pub const COSTS: Costs = Costs {
+    turn: 1000,
+    forward: 1,
+    tiles: false,
+};
+
+pub struct Costs {
+    pub turn: usize,
+    pub forward: usize,
+    /// Adds the digit of `0` to `9` cells to the cost of stepping onto them.
+    pub tiles: bool,
+}
+
+impl Costs {
+    fn step_onto(&self, cell: u8) -> usize {
+        match cell {
+            b'0'..=b'9' if self.tiles => self.forward + (cell - b'0') as usize,
+            _ => self.forward,
+        }
+    }
+}
+
+pub struct MazeSolution {
+    pub score: usize,
+    pub best_tiles: HashSet<Position>,
+    /// Number of distinct optimal routes, as sequences of steps and turns.
+    pub num_routes: u128,
+}
+
+/// How to draw the best-path tiles.
+#[allow(dead_code)]
+enum Render {
+    /// Coloured text printed to the terminal.
+    Ansi,
+    /// Binary PPM image written to the given path.
+    Ppm(&'static str),
+}
+
+#[derive(Copy, Clone, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct Vertex {
+    position: Position,
+    orientation: Orientation,
+}
+
+impl Debug for Vertex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} {:?}", self.orientation, self.position)
+    }
+}
+
+#[derive(Copy, Clone, Eq, Hash, PartialEq)]
+struct EdgeEnd {
+    end: Vertex,
+    cost: usize,
+}
+
+impl Debug for EdgeEnd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}+{:?}", self.end, self.cost)
+    }
+}
+
+#[derive(Default)]
+struct Graph {
+    vertices: HashSet<Vertex>,
+    edges: HashMap<Vertex, Vec<EdgeEnd>>,
+    start: Option<Vertex>,
+    end: Option<[Vertex; 2]>,
+}
+
+/// Result of `Graph::dijkstra`. `prev` only links to vertices settled earlier,
+/// so it forms a DAG that `order` sorts topologically.
+struct ShortestPaths {
+    dist: HashMap<Vertex, usize>,
+    prev: HashMap<Vertex, Vec<Vertex>>,
+    order: Vec<Vertex>,
+}
+
+impl Graph {
+    fn add_edge(
+        &mut self,
+        v1: Vertex,
+        v2: Vertex,
+        cost_12: usize,
+        cost_21: usize,
+    ) {
+        self.vertices.insert(v1);
+        self.vertices.insert(v2);
+        self.edges.entry(v1).or_default().push(EdgeEnd {
+            end: v2,
+            cost: cost_12,
+        });
+        self.edges.entry(v2).or_default().push(EdgeEnd {
+            end: v1,
+            cost: cost_21,
+        });
+    }
+
+    fn set_start(&mut self, start: Vertex) {
+        self.start = Some(start);
+    }
+
+    fn set_end(&mut self, end: Position) {
+        self.end = Some([
+            Vertex {
+                position: end,
+                orientation: Orientation::NS,
+            },
+            Vertex {
+                position: end,
+                orientation: Orientation::EW,
+            },
+        ]);
+    }
+
+    fn trim(&mut self) {
+        let required = [
+            self.start.unwrap(),
+            self.end.unwrap()[0],
+            self.end.unwrap()[1],
+        ];
+
+        if DEBUG {
+            println!(
+                "pre-trim {} {}",
+                self.vertices.len(),
+                self.edges.values().flat_map(|edges| edges.iter()).count()
+            );
+        }
+
+        loop {
+            let to_remove: Vec<_> = self
+                .edges
+                .iter()
+                .filter(|(v, edges)| !required.contains(v) && edges.len() <= 1)
+                .map(|(&v, edges)| (v, edges.clone()))
+                .collect();
+
+            if to_remove.is_empty() {
+                break;
+            }
+
+            to_remove.iter().for_each(|(v, edges)| {
+                edges.iter().for_each(|edge| {
+                    self.edges
+                        .get_mut(&edge.end)
+                        .unwrap()
+                        .retain(|edge| edge.end != *v);
+                });
+
+                self.edges.remove(v);
+                self.vertices.remove(v);
+            });
+        }
+
+        if DEBUG {
+            println!(
+                "post-trim {} {}",
+                self.vertices.len(),
+                self.edges.values().flat_map(|edges| edges.iter()).count()
+            );
+        }
+    }
+
+    fn dijkstra(&self) -> ShortestPaths {
+        let mut dist: HashMap<Vertex, usize> = HashMap::new();
+        let mut prev: HashMap<Vertex, Vec<Vertex>> = HashMap::new();
+        let mut order = Vec::new();
+        let mut settled = HashSet::new();
+
+        if let Some(start) = self.start {
+            let mut queue = BinaryHeap::from([Reverse((0, start))]);
+
+            dist.insert(start, 0);
+
+            while let Some(Reverse((d, min_vertex))) = queue.pop() {
+                if !settled.insert(min_vertex) {
+                    continue;
+                }
+
+                order.push(min_vertex);
+
+                self.edges
+                    .get(&min_vertex)
+                    .into_iter()
+                    .flatten()
+                    .filter(|EdgeEnd { end, .. }| !settled.contains(end))
+                    .for_each(|&EdgeEnd { end, cost }| {
+                        let new_d = d + cost;
+                        let end_d =
+                            dist.get(&end).copied().unwrap_or(usize::MAX);
+
+                        if new_d <= end_d {
+                            let end_prev = prev.entry(end).or_default();
+
+                            if new_d < end_d {
+                                dist.insert(end, new_d);
+                                end_prev.clear();
+                                queue.push(Reverse((new_d, end)));
+                            }
+
+                            end_prev.push(min_vertex);
+                        }
+                    })
+            }
+        }
+
+        ShortestPaths { dist, prev, order }
+    }
+
+    fn solve(&self) -> Option<MazeSolution> {
+        let paths = self.dijkstra();
+        let best_dists = self.shortest_path_dists(&paths);
+
+        let score = self
+            .end?
+            .iter()
+            .filter_map(|end| paths.dist.get(end))
+            .min()
+            .copied()?;
+
+        let mut num_routes: HashMap<Vertex, u128> = HashMap::new();
+
+        paths.order.iter().for_each(|vertex| {
+            let count = match paths.prev.get(vertex) {
+                Some(prevs) if Some(*vertex) != self.start => prevs
+                    .iter()
+                    .map(|prev| num_routes.get(prev).copied().unwrap_or(0))
+                    .sum(),
+                _ => 1,
+            };
+
+            num_routes.insert(*vertex, count);
+        });
+
+        Some(MazeSolution {
+            score,
+            best_tiles: best_dists
+                .keys()
+                .map(|&Vertex { position, .. }| position)
+                .collect(),
+            num_routes: self
+                .end?
+                .iter()
+                .filter(|end| paths.dist.get(end) == Some(&score))
+                .map(|end| num_routes[end])
+                .sum(),
+        })
+    }
+
+    /// Returns the vertices on any optimal path, with their distance from the
+    /// start.
+    fn shortest_path_dists(
+        &self,
+        paths: &ShortestPaths,
+    ) -> HashMap<Vertex, usize> {
+        let mut vertices = HashMap::new();
+
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            let ShortestPaths { dist, prev, .. } = paths;
+
+            let min_end_dist = end.iter().filter_map(|end| dist.get(end)).min();
+            let mut remaining = Vec::from_iter(
+                end.iter()
+                    .filter(|end| dist.get(end) == min_end_dist)
+                    .copied(),
+            );
+
+            if DEBUG {
+                println!(
+                    "{:?} {:?} {:?} {:?}",
+                    min_end_dist,
+                    end,
+                    end.iter().map(|end| dist.get(end)).collect::<Vec<_>>(),
+                    remaining
+                );
+            }
+
+            while let Some(vertex) = remaining.pop() {
+                if vertices.insert(vertex, dist[&vertex]).is_none()
+                    && vertex != start
+                {
+                    if let Some(prevs) = prev.get(&vertex) {
+                        if DEBUG {
+                            println!(
+                                "{:?} {:?} {:?}",
+                                dist.get(&vertex).unwrap(),
+                                vertex,
+                                prevs
+                            );
+                        }
+                        remaining.extend(prevs);
+                    }
+                }
+            }
+        }
+
+        vertices
+    }
+
+    fn edge_cost(&self, from: &Vertex, to: &Vertex) -> Option<usize> {
+        self.edges
+            .get(from)?
+            .iter()
+            .find(|edge| edge.end == *to)
+            .map(|edge| edge.cost)
+    }
+
+    /// Returns the facing after each turn taken on an optimal path.
+    fn shortest_path_turns(&self) -> HashMap<Position, u8> {
+        let dists = self.shortest_path_dists(&self.dijkstra());
+
+        dists
+            .iter()
+            .filter_map(|(vertex, &dist)| {
+                let other = Vertex {
+                    position: vertex.position,
+                    orientation: match vertex.orientation {
+                        Orientation::NS => Orientation::EW,
+                        Orientation::EW => Orientation::NS,
+                    },
+                };
+
+                // Only the orientation taken after the turn is kept.
+                let is_turn = dists.get(&other).is_some_and(|&other_dist| {
+                    self.edge_cost(&other, vertex)
+                        .is_some_and(|cost| other_dist + cost == dist)
+                });
+
+                if !is_turn {
+                    return None;
+                }
+
+                self.edges.get(vertex)?.iter().find_map(|edge| {
+                    let is_forward = edge.end.orientation == vertex.orientation
+                        && dists.get(&edge.end) == Some(&(dist + edge.cost));
+
+                    is_forward.then(|| {
+                        let Position { x, y } = vertex.position;
+
+                        let facing =
+                            match (edge.end.position.x, edge.end.position.y) {
+                                (end_x, _) if end_x > x => b'>',
+                                (end_x, _) if end_x < x => b'<',
+                                (_, end_y) if end_y > y => b'v',
+                                _ => b'^',
+                            };
+
+                        (vertex.position, facing)
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+impl Debug for Graph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut vertices_vec: Vec<_> = self.vertices.iter().collect();
+        vertices_vec.sort();
+
+        let mut edges_vec: Vec<_> = self.edges.iter().collect();
+        edges_vec.sort_by(|a, b| a.0.cmp(b.0));
+
+        writeln!(f, "Graph")?;
+        writeln!(f, "  Vertices")?;
+        for vertex in vertices_vec.iter() {
+            writeln!(f, "    {:?}", vertex)?;
+        }
+        writeln!(f, "  Edges")?;
+        for edge in edges_vec.iter() {
+            writeln!(f, "    {:?}", edge)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Position {
+    x: usize,
+    y: usize,
+}
+
+impl Debug for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+enum Orientation {
+    NS,
+    EW,
+}
+
+enum Tile {
+    Start,
+    End,
+    Wall,
+    Floor,
+    Best,
+    /// A best tile where a path turns, with the facing after the turn.
+    Turn(u8),
+}
+
+impl Tile {
+    fn new(cell: u8, is_best: bool, turn: Option<u8>) -> Self {
+        match (cell, is_best, turn) {
+            (b'S', ..) => Tile::Start,
+            (b'E', ..) => Tile::End,
+            (b'#', ..) => Tile::Wall,
+            (_, true, Some(facing)) => Tile::Turn(facing),
+            (_, true, None) => Tile::Best,
+            _ => Tile::Floor,
+        }
+    }
+
+    fn char(&self) -> char {
+        match self {
+            Tile::Start => 'S',
+            Tile::End => 'E',
+            Tile::Wall => '#',
+            Tile::Floor => '.',
+            Tile::Best => 'O',
+            Tile::Turn(facing) => *facing as char,
+        }
+    }
+
+    fn ansi_colour(&self) -> &'static str {
+        match self {
+            Tile::Start => "1;97;44",
+            Tile::End => "1;97;41",
+            Tile::Wall => "90",
+            Tile::Floor => "37",
+            Tile::Best => "1;92",
+            Tile::Turn(_) => "1;93",
+        }
+    }
+
+    fn rgb(&self) -> [u8; 3] {
+        match self {
+            Tile::Start => [40, 90, 220],
+            Tile::End => [220, 40, 40],
+            Tile::Wall => [60, 60, 60],
+            Tile::Floor => [230, 230, 230],
+            Tile::Best => [40, 180, 60],
+            Tile::Turn(_) => [240, 200, 40],
+        }
+    }
+}
+
+fn print_ansi(
+    map: &[Vec<u8>],
+    best: &HashSet<Position>,
+    turns: &HashMap<Position, u8>,
+) {
+    map.iter().enumerate().for_each(|(y, row)| {
+        row.iter().enumerate().for_each(|(x, &cell)| {
+            let position = Position { x, y };
+            let tile = Tile::new(
+                cell,
+                best.contains(&position),
+                turns.get(&position).copied(),
+            );
+
+            print!("\x1b[{}m{}", tile.ansi_colour(), tile.char());
+        });
+
+        println!("\x1b[0m");
+    });
+}
+
+fn write_ppm(
+    path: &str,
+    map: &[Vec<u8>],
+    best: &HashSet<Position>,
+    turns: &HashMap<Position, u8>,
+) {
+    let width = map[0].len() * PPM_SCALE;
+    let height = map.len() * PPM_SCALE;
+
+    let mut image = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+
+    (0..height).for_each(|py| {
+        (0..width).for_each(|px| {
+            let position = Position {
+                x: px / PPM_SCALE,
+                y: py / PPM_SCALE,
+            };
+            let tile = Tile::new(
+                map[position.y][position.x],
+                best.contains(&position),
+                turns.get(&position).copied(),
+            );
+
+            image.extend(tile.rgb());
+        });
+    });
+
+    fs::write(path, image).expect("cannot write file");
+}
+
+/// Finds the best routes through the maze from `S`, facing east, to `E`.
+pub fn solve_maze(costs: &Costs) -> Option<MazeSolution> {
+    let data = fs::read_to_string("data/day_16.txt").expect("missing file");
+
+    let map: Vec<Vec<_>> = data
+        .split('\n')
+        .map(|line| line.as_bytes().to_vec())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let max_x = map[0].len();
+    let max_y = map.len();
+
+    let mut graph = Graph::default();
+
+    (0..max_y).for_each(|y| {
+        (0..max_x).for_each(|x| {
+            if map[y][x] != b'#' {
+                let position = Position { x, y };
+                let vertex_ns = Vertex {
+                    position,
+                    orientation: Orientation::NS,
+                };
+                let vertex_ew = Vertex {
+                    position,
+                    orientation: Orientation::EW,
+                };
+                let step_cost = costs.step_onto(map[y][x]);
+
+                // Moving between the vertical and horizontal paths.
+                graph.add_edge(vertex_ns, vertex_ew, costs.turn, costs.turn);
+
+                // Moving between horizontally adjacent nodes.
+                if x > 0 && map[y][x - 1] != b'#' {
+                    let vertex_left_ew = Vertex {
+                        position: Position { x: x - 1, y },
+                        orientation: Orientation::EW,
+                    };
+                    graph.add_edge(
+                        vertex_ew,
+                        vertex_left_ew,
+                        costs.step_onto(map[y][x - 1]),
+                        step_cost,
+                    );
+                }
+
+                // Moving between vertically adjacent nodes.
+                if y > 0 && map[y - 1][x] != b'#' {
+                    let vertex_up_ns = Vertex {
+                        position: Position { x, y: y - 1 },
+                        orientation: Orientation::NS,
+                    };
+                    graph.add_edge(
+                        vertex_ns,
+                        vertex_up_ns,
+                        costs.step_onto(map[y - 1][x]),
+                        step_cost,
+                    );
+                }
+
+                match map[y][x] {
+                    b'S' => graph.set_start(vertex_ew),
+                    b'E' => graph.set_end(position),
+                    _ => (),
+                }
+            }
+        });
+    });
+
+    graph.trim();
+
+    if DEBUG {
+        println!("{:#?}", graph);
+    }
+
+    let solution = graph.solve()?;
+
+    if let Some(render) = RENDER {
+        let turns = if SHOW_TURNS {
+            graph.shortest_path_turns()
+        } else {
+            HashMap::new()
+        };
+
+        match render {
+            Render::Ansi => print_ansi(&map, &solution.best_tiles, &turns),
+            Render::Ppm(path) => {
+                write_ppm(path, &map, &solution.best_tiles, &turns)
+            }
+        }
+    }
+
+    if SHOW_STATS {
+        println!(
+            "score {}, best tiles {}, best routes {}",
+            solution.score,
+            solution.best_tiles.len(),
+            solution.num_routes
+        );
+    }
+
+    Some(solution)
+}
@@ -0,0 +1,336 @@
+use std::{collections::VecDeque, fs};
+
+const IS_TEST: bool = false;
+const TRACE: bool = false;
+
+/// Side length of the memory space, or `None` to infer it from the largest
+/// coordinate. Bytes outside a given size are ignored.
+pub const GRID_SIZE: Option<usize> = if IS_TEST { Some(7) } else { Some(71) };
+/// Number of bytes that have fallen when part 1 looks for a path.
+pub const NUM_BYTES: usize = if IS_TEST { 12 } else { 1024 };
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Vertex {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// Union-find over the cells of the memory grid, indexed by `y * size + x`.
+struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+
+        if a != b {
+            let (big, small) = if self.size[a] < self.size[b] {
+                (b, a)
+            } else {
+                (a, b)
+            };
+
+            self.parent[small] = big;
+            self.size[big] += self.size[small];
+        }
+    }
+}
+
+/// A fallen byte that cuts regions off the one reachable from the start.
+pub struct Split {
+    pub index: usize,
+    pub byte: Vertex,
+    pub region_sizes: Vec<usize>,
+}
+
+pub enum Blocking {
+    /// Index of the first byte after which the exit cannot be reached.
+    Byte(usize),
+    NeverBlocked,
+}
+
+pub struct MemorySpace {
+    pub size: usize,
+    pub bytes: Vec<Vertex>,
+}
+
+/// Drops all the bytes, then adds them back in reverse order until the start
+/// and the end join. Returns the index of the first blocking byte and, in
+/// falling order, every byte that splits the region reachable from the start.
+fn first_blocking_byte(
+    corrupted_memory_list: &[Vertex],
+    size: usize,
+) -> (Blocking, Vec<Split>) {
+    let cell = |Vertex { x, y }: Vertex| y * size + x;
+    let start = 0;
+    let end = size * size - 1;
+
+    let mut fall_index = vec![None; size * size];
+
+    corrupted_memory_list
+        .iter()
+        .enumerate()
+        .rev()
+        .for_each(|(i, &byte)| fall_index[cell(byte)] = Some(i));
+
+    let mut is_free =
+        fall_index.iter().map(Option::is_none).collect::<Vec<_>>();
+    let mut regions = DisjointSet::new(size * size);
+
+    let neighbours = |i: usize| {
+        let (x, y) = (i % size, i / size);
+
+        [
+            (x > 0).then(|| i - 1),
+            (x + 1 < size).then(|| i + 1),
+            (y > 0).then(|| i - size),
+            (y + 1 < size).then(|| i + size),
+        ]
+        .into_iter()
+        .flatten()
+    };
+
+    (0..size * size).for_each(|i| {
+        if is_free[i] {
+            neighbours(i)
+                .filter(|&j| j > i && is_free[j])
+                .for_each(|j| regions.union(i, j));
+        }
+    });
+
+    let mut blocking = Blocking::NeverBlocked;
+    let mut splits = Vec::new();
+    let mut is_joined = is_free[start]
+        && is_free[end]
+        && regions.find(start) == regions.find(end);
+
+    corrupted_memory_list
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|&(i, &byte)| fall_index[cell(byte)] == Some(i))
+        .for_each(|(index, &byte)| {
+            let i = cell(byte);
+
+            is_free[i] = true;
+
+            let mut roots = neighbours(i)
+                .filter(|&j| is_free[j])
+                .map(|j| regions.find(j))
+                .collect::<Vec<_>>();
+
+            roots.sort_unstable();
+            roots.dedup();
+
+            let start_root = is_free[start].then(|| regions.find(start));
+
+            if let Some(start_root) =
+                start_root.filter(|root| roots.contains(root))
+            {
+                if roots.len() > 1 {
+                    splits.push(Split {
+                        index,
+                        byte,
+                        region_sizes: roots
+                            .iter()
+                            .filter(|&&root| root != start_root)
+                            .map(|&root| regions.size[root])
+                            .collect(),
+                    });
+                }
+            }
+
+            roots.iter().for_each(|&root| regions.union(i, root));
+
+            if !is_joined
+                && is_free[start]
+                && is_free[end]
+                && regions.find(start) == regions.find(end)
+            {
+                is_joined = true;
+                blocking = Blocking::Byte(index);
+            }
+        });
+
+    splits.reverse();
+
+    (blocking, splits)
+}
+
+impl MemorySpace {
+    pub fn load(grid_size: Option<usize>) -> Self {
+        let data = fs::read_to_string(if IS_TEST {
+            "data/day_18_test.txt"
+        } else {
+            "data/day_18.txt"
+        })
+        .expect("missing file");
+
+        let bytes = data
+            .split('\n')
+            .filter_map(|line| {
+                line.split_once(',').and_then(|(x, y)| {
+                    match (x.parse::<usize>(), y.parse::<usize>()) {
+                        (Ok(x), Ok(y)) => Some(Vertex { x, y }),
+                        _ => None,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let size = grid_size.unwrap_or_else(|| {
+            bytes
+                .iter()
+                .map(|byte| byte.x.max(byte.y) + 1)
+                .max()
+                .unwrap_or(1)
+        });
+
+        Self {
+            size,
+            bytes: bytes
+                .into_iter()
+                .filter(|byte| byte.x < size && byte.y < size)
+                .collect(),
+        }
+    }
+
+    /// Returns the shortest path from the top left to the bottom right corner
+    /// after the first `num_bytes` bytes have fallen.
+    pub fn shortest_path(&self, num_bytes: usize) -> Option<Vec<Vertex>> {
+        let mut is_corrupted = vec![false; self.size * self.size];
+
+        self.bytes.iter().take(num_bytes).for_each(|byte| {
+            is_corrupted[byte.y * self.size + byte.x] = true;
+        });
+
+        self.find_path(&is_corrupted)
+    }
+
+    fn find_path(&self, is_corrupted: &[bool]) -> Option<Vec<Vertex>> {
+        let size = self.size;
+        let end = size * size - 1;
+
+        if is_corrupted[0] || is_corrupted[end] {
+            return None;
+        }
+
+        let mut prev = vec![None; size * size];
+        let mut queue = VecDeque::from([0]);
+
+        prev[0] = Some(0);
+
+        while let Some(i) = queue.pop_front() {
+            if i == end {
+                let mut path = vec![Vertex {
+                    x: i % size,
+                    y: i / size,
+                }];
+                let mut i = i;
+
+                while i != 0 {
+                    i = prev[i]?;
+                    path.push(Vertex {
+                        x: i % size,
+                        y: i / size,
+                    });
+                }
+
+                path.reverse();
+
+                return Some(path);
+            }
+
+            let (x, y) = (i % size, i / size);
+
+            [
+                (x > 0).then(|| i - 1),
+                (x + 1 < size).then(|| i + 1),
+                (y > 0).then(|| i - size),
+                (y + 1 < size).then(|| i + size),
+            ]
+            .into_iter()
+            .flatten()
+            .for_each(|j| {
+                if !is_corrupted[j] && prev[j].is_none() {
+                    prev[j] = Some(i);
+                    queue.push_back(j);
+                }
+            });
+        }
+
+        None
+    }
+
+    pub fn first_blocking_byte(&self) -> (Blocking, Vec<Split>) {
+        if TRACE {
+            self.print_trace();
+        }
+
+        first_blocking_byte(&self.bytes, self.size)
+    }
+
+    /// Prints the memory space with the shortest path after each byte falls,
+    /// until the exit cannot be reached any more.
+    fn print_trace(&self) {
+        let mut is_corrupted = vec![false; self.size * self.size];
+        let mut path = self.find_path(&is_corrupted);
+
+        for (index, byte) in self.bytes.iter().enumerate() {
+            is_corrupted[byte.y * self.size + byte.x] = true;
+
+            if path.as_ref().is_none_or(|path| path.contains(byte)) {
+                path = self.find_path(&is_corrupted);
+            }
+
+            let Some(path) = &path else {
+                println!(
+                    "byte {} at {},{} blocks the path",
+                    index, byte.x, byte.y
+                );
+                break;
+            };
+
+            println!(
+                "byte {} at {},{}: {} steps",
+                index,
+                byte.x,
+                byte.y,
+                path.len() - 1
+            );
+
+            let mut map = is_corrupted
+                .chunks(self.size)
+                .map(|row| {
+                    row.iter()
+                        .map(|&corrupted| if corrupted { '#' } else { '.' })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            path.iter().for_each(|v| map[v.y][v.x] = 'O');
+
+            map.iter()
+                .for_each(|row| println!("{}", row.iter().collect::<String>()));
+            println!();
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    cmp::Reverse,
+    collections::{
+        hash_map::Entry, BTreeMap, BinaryHeap, HashMap, HashSet, VecDeque,
+    },
+    fs,
+};
+
+pub const IS_TEST: bool = false;
+const LIST_CHEATS: bool = false;
+const SHOW_HISTOGRAM: bool = false;
+const EXPORT_CSV: Option<&str> = None;
+/// Whether a cheat may also pass through track cells.
+const CROSSING: Crossing = Crossing::Any;
+/// Cheats allowed in the fastest race, which is printed when above one. The
+/// listing, histogram, CSV and count always use a single cheat.
+const MAX_CHEATS: usize = 1;
+
+/// Cells a cheat may pass through before it ends back on the track.
+#[allow(dead_code)]
+enum Crossing {
+    WallsOnly,
+    Any,
+}
+
+struct CheatRules {
+    budget: usize,
+    max_cheats: usize,
+    crossing: Crossing,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+struct Vertex {
+    x: usize,
+    y: usize,
+}
+
+/// A cheat from one track position to another, `length` picoseconds apart.
+struct Cheat {
+    start: Vertex,
+    end: Vertex,
+    length: usize,
+    saving: usize,
+}
+
+struct RaceTrack {
+    map: Vec<Vec<u8>>,
+    start: Vertex,
+    end: Vertex,
+    /// Distances from the start and to the end without cheating, which stay
+    /// valid on tracks with more than one path.
+    dist_start: HashMap<Vertex, usize>,
+    dist_end: HashMap<Vertex, usize>,
+}
+
+impl RaceTrack {
+    fn load() -> Self {
+        let data = fs::read_to_string(if IS_TEST {
+            "data/day_20_test.txt"
+        } else {
+            "data/day_20.txt"
+        })
+        .expect("missing file");
+
+        let map = data
+            .split('\n')
+            .take_while(|line| !line.is_empty())
+            .map(|line| line.as_bytes().to_vec())
+            .collect::<Vec<_>>();
+
+        let mut start = Vertex { x: 0, y: 0 };
+        let mut end = Vertex { x: 0, y: 0 };
+
+        map.iter().enumerate().for_each(|(y, line)| {
+            line.iter().enumerate().for_each(|(x, &cell)| match cell {
+                b'S' => start = Vertex { x, y },
+                b'E' => end = Vertex { x, y },
+                _ => (),
+            });
+        });
+
+        let mut race_track = Self {
+            map,
+            start,
+            end,
+            dist_start: HashMap::new(),
+            dist_end: HashMap::new(),
+        };
+
+        race_track.dist_start = race_track.track_distances(start);
+        race_track.dist_end = race_track.track_distances(end);
+
+        race_track
+    }
+
+    /// Distances from `source` to the track positions it can reach, by a
+    /// breadth-first search since every move takes one picosecond.
+    fn track_distances(&self, source: Vertex) -> HashMap<Vertex, usize> {
+        let mut dist = HashMap::from([(source, 0)]);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(vertex) = queue.pop_front() {
+            let next_dist = dist[&vertex] + 1;
+
+            self.neighbours(vertex)
+                .filter(|&next| self.is_track(next))
+                .for_each(|next| {
+                    if let Entry::Vacant(entry) = dist.entry(next) {
+                        entry.insert(next_dist);
+                        queue.push_back(next);
+                    }
+                });
+        }
+
+        dist
+    }
+
+    fn neighbours(
+        &self,
+        Vertex { x, y }: Vertex,
+    ) -> impl Iterator<Item = Vertex> {
+        let (xmax, ymax) = (self.map[0].len(), self.map.len());
+
+        [
+            (x > 0).then(|| Vertex { x: x - 1, y }),
+            (x + 1 < xmax).then(|| Vertex { x: x + 1, y }),
+            (y > 0).then(|| Vertex { x, y: y - 1 }),
+            (y + 1 < ymax).then(|| Vertex { x, y: y + 1 }),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    fn is_track(&self, Vertex { x, y }: Vertex) -> bool {
+        self.map[y][x] != b'#'
+    }
+
+    /// Returns the track positions a cheat from `start` can end on, with the
+    /// length of the shortest such cheat.
+    fn cheat_ends(
+        &self,
+        start: Vertex,
+        rules: &CheatRules,
+    ) -> Vec<(Vertex, usize)> {
+        let budget = rules.budget;
+
+        match rules.crossing {
+            Crossing::Any => {
+                let Vertex { x, y } = start;
+                let (xmax, ymax) = (self.map[0].len(), self.map.len());
+
+                ((y.saturating_sub(budget))
+                    ..=(y.saturating_add(budget)).min(ymax - 1))
+                    .flat_map(|y2| {
+                        let x_range = budget - y2.abs_diff(y);
+
+                        (x.saturating_sub(x_range)
+                            ..=x.saturating_add(x_range).min(xmax - 1))
+                            .map(move |x2| Vertex { x: x2, y: y2 })
+                    })
+                    .filter(|&end| self.is_track(end))
+                    .map(|end| (end, end.x.abs_diff(x) + end.y.abs_diff(y)))
+                    .collect()
+            }
+            Crossing::WallsOnly => {
+                let mut seen = HashSet::from([start]);
+                let mut ends = Vec::new();
+                let mut queue = VecDeque::from([(start, 0)]);
+
+                while let Some((vertex, length)) = queue.pop_front() {
+                    if length == budget {
+                        continue;
+                    }
+
+                    self.neighbours(vertex).for_each(|next| {
+                        if seen.insert(next) {
+                            if self.is_track(next) {
+                                ends.push((next, length + 1));
+                            } else {
+                                queue.push_back((next, length + 1));
+                            }
+                        }
+                    });
+                }
+
+                // Stepping onto a neighbouring track cell is not a cheat.
+                ends.into_iter().filter(|&(_, length)| length > 1).collect()
+            }
+        }
+    }
+
+    /// Returns the single cheats that save at least `threshold`, ordered by
+    /// start then end position.
+    fn find_cheats(&self, rules: &CheatRules, threshold: usize) -> Vec<Cheat> {
+        let Some(&race_time) = self.dist_start.get(&self.end) else {
+            return Vec::new();
+        };
+
+        let mut cheats = self
+            .dist_start
+            .iter()
+            .flat_map(|(&start, &dist_v1)| {
+                self.cheat_ends(start, rules).into_iter().filter_map(
+                    move |(end, length)| {
+                        let time =
+                            dist_v1 + length + self.dist_end.get(&end)?;
+
+                        (time + threshold <= race_time).then(|| Cheat {
+                            start,
+                            end,
+                            length,
+                            saving: race_time - time,
+                        })
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        cheats.sort_unstable_by_key(|cheat| (cheat.start, cheat.end));
+
+        cheats
+    }
+
+    /// Returns the fastest race time using at most `rules.max_cheats` cheats.
+    fn fastest_race(&self, rules: &CheatRules) -> Option<usize> {
+        let mut settled = HashSet::new();
+        let mut queue = BinaryHeap::from([Reverse((0, 0, self.start))]);
+
+        while let Some(Reverse((time, num_cheats, vertex))) = queue.pop() {
+            if vertex == self.end {
+                return Some(time);
+            }
+
+            if !settled.insert((vertex, num_cheats)) {
+                continue;
+            }
+
+            self.neighbours(vertex)
+                .filter(|&next| self.is_track(next))
+                .for_each(|next| {
+                    queue.push(Reverse((time + 1, num_cheats, next)));
+                });
+
+            if num_cheats < rules.max_cheats {
+                self.cheat_ends(vertex, rules).into_iter().for_each(
+                    |(next, length)| {
+                        queue.push(Reverse((
+                            time + length,
+                            num_cheats + 1,
+                            next,
+                        )));
+                    },
+                );
+            }
+        }
+
+        None
+    }
+}
+
+fn write_csv(path: &str, cheats: &[Cheat]) {
+    let csv = cheats.iter().fold(
+        String::from("start_x,start_y,end_x,end_y,length,saving\n"),
+        |csv, cheat| {
+            csv + &format!(
+                "{},{},{},{},{},{}\n",
+                cheat.start.x,
+                cheat.start.y,
+                cheat.end.x,
+                cheat.end.y,
+                cheat.length,
+                cheat.saving
+            )
+        },
+    );
+
+    fs::write(path, csv).expect("cannot write file");
+}
+
+pub fn print_valid_cheats(cheat_length: usize, threshold: usize) {
+    let rules = CheatRules {
+        budget: cheat_length,
+        max_cheats: MAX_CHEATS,
+        crossing: CROSSING,
+    };
+    let race_track = RaceTrack::load();
+    let cheats = race_track.find_cheats(&rules, threshold);
+
+    if LIST_CHEATS {
+        cheats.iter().for_each(|cheat| {
+            println!(
+                "{},{} -> {},{}: {} picoseconds, saves {}",
+                cheat.start.x,
+                cheat.start.y,
+                cheat.end.x,
+                cheat.end.y,
+                cheat.length,
+                cheat.saving
+            );
+        });
+    }
+
+    if SHOW_HISTOGRAM {
+        let mut histogram = BTreeMap::<usize, usize>::new();
+
+        cheats.iter().for_each(|cheat| {
+            *histogram.entry(cheat.saving).or_default() += 1;
+        });
+
+        histogram.iter().for_each(|(saving, count)| match count {
+            1 => {
+                println!("There is one cheat that saves {saving} picoseconds.")
+            }
+            _ => println!(
+                "There are {count} cheats that save {saving} picoseconds."
+            ),
+        });
+    }
+
+    if let Some(path) = EXPORT_CSV {
+        write_csv(path, &cheats);
+    }
+
+    if rules.max_cheats > 1 {
+        match race_track.fastest_race(&rules) {
+            Some(time) => println!(
+                "fastest race with up to {} cheats: {} picoseconds",
+                rules.max_cheats, time
+            ),
+            None => println!("the end cannot be reached"),
+        }
+    }
+
+    println!("{}", cheats.len());
+}
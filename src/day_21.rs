@@ -0,0 +1,663 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    fs,
+    iter::repeat_n,
+};
+
+const DEBUG: bool = false;
+const SHOW_SEQUENCES: bool = false;
+const MAX_EXPANDED_LEN: usize = 200;
+/// File with the numpad layout, an empty line, then the dirpad layout, to use
+/// instead of the puzzle's pads.
+const LAYOUTS: Option<&str> = None;
+/// File with one sequence per line, typed by the user on the outermost dirpad,
+/// to replay instead of solving the codes.
+const REPLAY: Option<&str> = None;
+
+struct Code<'a> {
+    /// Leading digits of the code, if any, which its complexity scales by.
+    num: Option<usize>,
+    buttons: &'a [u8],
+}
+
+type KeyDef = ((usize, usize), u8);
+type MinMoves = (usize, Vec<u8>);
+
+const NUMPAD_LAYOUT: &str = "\
+789
+456
+123
+#0A";
+
+const DIRPAD_LAYOUT: &str = "\
+#^A
+<v>";
+
+/// Reads the keys of a pad drawn as a grid of its labels, with `#` or a space
+/// marking the gap.
+fn parse_layout(src: &str) -> Vec<KeyDef> {
+    src.lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.bytes()
+                .enumerate()
+                .filter(|&(_, key)| key != b'#' && key != b' ')
+                .map(move |(x, key)| ((x, y), key))
+        })
+        .collect()
+}
+
+fn format_ascii(seq: &[u8]) -> String {
+    String::from_utf8(seq.to_vec()).unwrap()
+}
+
+/// Writes runs of a repeated press as the count followed by the press, such as
+/// `<3^A` for `<^^^A`. Sequences with digit keys are written as is, since a
+/// count would read as one of them.
+fn format_run_length(seq: &[u8]) -> String {
+    if seq.iter().any(u8::is_ascii_digit) {
+        return format_ascii(seq);
+    }
+
+    seq.chunk_by(|a, b| a == b)
+        .map(|run| match run.len() {
+            1 => format_ascii(run),
+            len => format!("{}{}", len, run[0] as char),
+        })
+        .collect()
+}
+
+/// Generates the candidate moves from `(x1, y1)` to `(x2, y2)`, each followed
+/// by `A`. Turning costs presses on the next pad, so only the two L-shaped
+/// paths are kept. When the gap blocks both, the monotone path with the fewest
+/// turns is used instead.
+fn gen_paths(
+    (x1, y1): (usize, usize),
+    (x2, y2): (usize, usize),
+    keys: &HashMap<(usize, usize), u8>,
+) -> HashSet<Vec<u8>> {
+    let dx = x2.abs_diff(x1);
+    let dy = y2.abs_diff(y1);
+
+    let horizontal_move = if x2 > x1 { b'>' } else { b'<' };
+    let vertical_move = if y2 > y1 { b'v' } else { b'^' };
+
+    let horizontal_moves = repeat_n(horizontal_move, dx);
+    let vertical_moves = repeat_n(vertical_move, dy);
+
+    let mut paths = [
+        horizontal_moves.clone().chain(vertical_moves.clone()),
+        vertical_moves.chain(horizontal_moves),
+    ]
+    .into_iter()
+    .map(|moves| moves.chain([b'A']).collect::<Vec<_>>())
+    .filter(|moves| is_valid_path((x1, y1), moves, keys))
+    .collect::<HashSet<_>>();
+
+    if paths.is_empty() {
+        paths.extend(gen_monotone_path((x1, y1), (x2, y2), keys));
+    }
+
+    paths
+}
+
+fn is_valid_path(
+    start: (usize, usize),
+    moves: &[u8],
+    keys: &HashMap<(usize, usize), u8>,
+) -> bool {
+    moves
+        .iter()
+        .filter(|&&m| m != b'A')
+        .try_fold(start, |last_pos, &m| {
+            move_position(last_pos, m)
+                .filter(|new_pos| keys.contains_key(new_pos))
+        })
+        .is_some()
+}
+
+/// Finds the path with the fewest turns among those that only step towards
+/// `(x2, y2)` and avoid the gap.
+fn gen_monotone_path(
+    (x1, y1): (usize, usize),
+    (x2, y2): (usize, usize),
+    keys: &HashMap<(usize, usize), u8>,
+) -> Option<Vec<u8>> {
+    let dx = x2.abs_diff(x1);
+    let dy = y2.abs_diff(y1);
+
+    let horizontal_move = if x2 > x1 { b'>' } else { b'<' };
+    let vertical_move = if y2 > y1 { b'v' } else { b'^' };
+
+    let position = |i: usize, j: usize| {
+        (
+            if x2 > x1 { x1 + i } else { x1 - i },
+            if y2 > y1 { y1 + j } else { y1 - j },
+        )
+    };
+
+    // Fewest turns to reach the offset `(i, j)` with the last move, and the
+    // previous move, indexed by `(i, j, is_vertical)`.
+    let mut turns: HashMap<(usize, usize, bool), (usize, Option<bool>)> =
+        HashMap::from_iter([
+            ((0, 0, false), (0, None)),
+            ((0, 0, true), (0, None)),
+        ]);
+
+    for i in 0..=dx {
+        for j in 0..=dy {
+            if !keys.contains_key(&position(i, j)) {
+                continue;
+            }
+
+            [
+                (i > 0, i.wrapping_sub(1), j, false),
+                (j > 0, i, j.wrapping_sub(1), true),
+            ]
+            .into_iter()
+            .filter(|&(is_inside, ..)| is_inside)
+            .for_each(|(_, prev_i, prev_j, is_vertical)| {
+                let best = [false, true]
+                    .into_iter()
+                    .filter_map(|was_vertical| {
+                        turns.get(&(prev_i, prev_j, was_vertical)).map(
+                            |&(count, _)| {
+                                let turn = (prev_i, prev_j) != (0, 0)
+                                    && was_vertical != is_vertical;
+
+                                (count + turn as usize, was_vertical)
+                            },
+                        )
+                    })
+                    .min();
+
+                if let Some((count, was_vertical)) = best {
+                    turns.insert(
+                        (i, j, is_vertical),
+                        (count, Some(was_vertical)),
+                    );
+                }
+            });
+        }
+    }
+
+    let mut is_vertical = [false, true]
+        .into_iter()
+        .filter_map(|is_vertical| {
+            turns
+                .get(&(dx, dy, is_vertical))
+                .map(|&(count, _)| (count, is_vertical))
+        })
+        .min()?
+        .1;
+
+    let mut moves = vec![b'A'];
+    let (mut i, mut j) = (dx, dy);
+
+    while (i, j) != (0, 0) {
+        let (_, prev) = turns[&(i, j, is_vertical)];
+
+        if is_vertical {
+            moves.push(vertical_move);
+            j -= 1;
+        } else {
+            moves.push(horizontal_move);
+            i -= 1;
+        }
+
+        is_vertical = prev.unwrap_or(is_vertical);
+    }
+
+    moves.reverse();
+
+    Some(moves)
+}
+
+fn move_position((x, y): (usize, usize), m: u8) -> Option<(usize, usize)> {
+    match m {
+        b'<' => x.checked_sub(1).map(|x| (x, y)),
+        b'^' => y.checked_sub(1).map(|y| (x, y)),
+        b'>' => Some((x + 1, y)),
+        b'v' => Some((x, y + 1)),
+        _ => None,
+    }
+}
+
+#[derive(Debug)]
+struct ReplayError {
+    /// Index of the user's press that moved the arm.
+    step: usize,
+    press: u8,
+    pad: String,
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "press {} ({}) points the arm of {} at the gap",
+            self.step, self.press as char, self.pad
+        )
+    }
+}
+
+struct Pad {
+    name: String,
+    keys: HashMap<(usize, usize), u8>,
+    move_sets: HashMap<(u8, u8), HashSet<Vec<u8>>>,
+    subpad: Option<Box<Pad>>,
+    cache: HashMap<(u8, u8), Option<MinMoves>>,
+}
+
+/// The presses typed on one pad of the chain for an optimal sequence. The
+/// whole sequence is not stored since it grows exponentially with the depth.
+struct SequenceLayer {
+    name: String,
+    len: usize,
+    /// For each move between two keys of this pad, the presses typed on the
+    /// next pad of the chain, or by the user for the last one. Each move ends
+    /// on `A`, so each pad's sequence is its moves replayed in order.
+    moves: Vec<((u8, u8), Vec<u8>)>,
+}
+
+impl Pad {
+    fn new(name: String, keys: &[KeyDef]) -> Self {
+        let keys_hash = keys.iter().copied().collect::<HashMap<_, _>>();
+
+        let move_sets = keys
+            .iter()
+            .flat_map(|&(position1, key1)| {
+                let keys_hash = &keys_hash;
+
+                keys.iter().map(move |&(position2, key2)| {
+                    ((key1, key2), gen_paths(position1, position2, keys_hash))
+                })
+            })
+            .collect();
+
+        Self {
+            name,
+            keys: keys_hash,
+            move_sets,
+            subpad: None,
+            cache: HashMap::new(),
+        }
+    }
+
+    fn get_sequence_min(&mut self, seq: &[u8]) -> Option<usize> {
+        if DEBUG {
+            println!("start {} {}", self.name, format_ascii(seq));
+        }
+
+        let min = seq
+            .iter()
+            .try_fold((0, b'A'), |(len, prev), &button| {
+                self.get_min_dist(prev, button)
+                    .map(|min| (len + min, button))
+            })
+            .map(|(len, _)| len);
+
+        if DEBUG {
+            println!(
+                "end {} {:?} {}",
+                self.name,
+                min,
+                String::from_utf8(seq.to_vec()).unwrap()
+            );
+        }
+
+        min
+    }
+
+    fn get_min_dist(&mut self, k1: u8, k2: u8) -> Option<usize> {
+        self.get_min_moves(k1, k2).map(|(len, _)| len)
+    }
+
+    /// Returns the optimal presses on the next pad to move from `k1` to `k2`
+    /// and press it, with the length they expand to on the last pad.
+    fn get_min_moves(&mut self, k1: u8, k2: u8) -> Option<MinMoves> {
+        if let Some(min) = self.cache.get(&(k1, k2)) {
+            return min.clone();
+        }
+
+        let min = {
+            if DEBUG {
+                println!(
+                    "  start {} {} {} {:?}",
+                    self.name,
+                    k1 as char,
+                    k2 as char,
+                    self.move_sets.get(&(k1, k2))
+                );
+            }
+
+            let min = self.move_sets.get(&(k1, k2)).and_then(|move_set| {
+                move_set
+                    .iter()
+                    .filter_map(|moves| {
+                        match &mut self.subpad {
+                            Some(subpad) => subpad.get_sequence_min(moves),
+                            _ => Some(moves.len()),
+                        }
+                        .map(|len| (len, moves.clone()))
+                    })
+                    // Ties are broken by the moves to keep sequences stable.
+                    .min()
+            });
+
+            if DEBUG {
+                println!(
+                    "  end {} {} {} {:?} {:?}",
+                    self.name,
+                    k1 as char,
+                    k2 as char,
+                    min,
+                    self.move_sets.get(&(k1, k2))
+                );
+            }
+
+            min
+        };
+
+        self.cache.insert((k1, k2), min.clone());
+
+        min
+    }
+
+    /// Reconstructs an optimal sequence for typing `seq` on this pad as one
+    /// layer per pad, this one first.
+    fn get_sequence_layers(
+        &mut self,
+        seq: &[u8],
+    ) -> Option<Vec<SequenceLayer>> {
+        let mut pair_counts = Vec::new();
+
+        seq.iter().fold(b'A', |prev, &button| {
+            match pair_counts
+                .iter_mut()
+                .find(|(pair, _)| *pair == (prev, button))
+            {
+                Some((_, count)) => *count += 1,
+                None => pair_counts.push(((prev, button), 1)),
+            }
+
+            button
+        });
+
+        let mut layers = Vec::new();
+        let mut pad = Some(self);
+
+        while let Some(current) = pad {
+            let moves = pair_counts
+                .iter()
+                .map(|&((k1, k2), _)| {
+                    current
+                        .get_min_moves(k1, k2)
+                        .map(|(_, moves)| ((k1, k2), moves))
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            let mut next_pair_counts: Vec<((u8, u8), usize)> = Vec::new();
+
+            pair_counts.iter().zip(moves.iter()).for_each(
+                |(&(_, count), (_, presses))| {
+                    presses.iter().fold(b'A', |prev, &button| {
+                        match next_pair_counts
+                            .iter_mut()
+                            .find(|(pair, _)| *pair == (prev, button))
+                        {
+                            Some((_, next_count)) => *next_count += count,
+                            None => {
+                                next_pair_counts.push(((prev, button), count))
+                            }
+                        }
+
+                        button
+                    });
+                },
+            );
+
+            layers.push(SequenceLayer {
+                name: current.name.clone(),
+                len: pair_counts.iter().map(|(_, count)| count).sum(),
+                moves,
+            });
+
+            pair_counts = next_pair_counts;
+            pad = current.subpad.as_deref_mut();
+        }
+
+        Some(layers)
+    }
+
+    /// Simulates the robot arms for the presses typed by the user on the
+    /// outermost dirpad, and returns the keys pressed on this pad.
+    fn replay(&self, presses: &[u8]) -> Result<Vec<u8>, ReplayError> {
+        let mut pads = Vec::new();
+        let mut pad = Some(self);
+
+        while let Some(current) = pad {
+            pads.push(current);
+            pad = current.subpad.as_deref();
+        }
+
+        // Every arm starts on the `A` key of its pad.
+        let mut arms = pads
+            .iter()
+            .map(|pad| {
+                pad.keys
+                    .iter()
+                    .find(|&(_, &key)| key == b'A')
+                    .map(|(&position, _)| position)
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        let mut output = Vec::new();
+
+        for (step, &press) in presses.iter().enumerate() {
+            let mut key = press;
+
+            // `key` is pressed on the pad controlling `pads[level]`.
+            for level in (0..pads.len()).rev() {
+                let pad = pads[level];
+
+                if key != b'A' {
+                    arms[level] = move_position(arms[level], key)
+                        .filter(|position| pad.keys.contains_key(position))
+                        .ok_or_else(|| ReplayError {
+                            step,
+                            press,
+                            pad: pad.name.clone(),
+                        })?;
+
+                    break;
+                }
+
+                key = pad.keys[&arms[level]];
+
+                if level == 0 {
+                    output.push(key);
+                }
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn chain(self, name: String, keys: &[KeyDef]) -> Pad {
+        let mut pad = Self::new(name, keys);
+        pad.subpad = Some(Box::new(self));
+
+        pad
+    }
+
+    /// Builds the numpad controlled through `num_dirpads` dirpads, the last
+    /// of which is used by the user.
+    fn new_chain(
+        num_dirpads: usize,
+        numpad_keys: &[KeyDef],
+        dirpad_keys: &[KeyDef],
+    ) -> Pad {
+        (1..=num_dirpads)
+            .rev()
+            .map(|i| (format!("Dirpad-{}", i), dirpad_keys))
+            .chain([("Numpad".to_string(), numpad_keys)])
+            .fold(None, |subpad: Option<Pad>, (name, keys)| match subpad {
+                Some(subpad) => Some(subpad.chain(name, keys)),
+                None => Some(Pad::new(name, keys)),
+            })
+            .unwrap()
+    }
+}
+
+/// Expands the layers of a sequence typed on the first pad into the presses
+/// typed on each pad, then by the user.
+fn expand_layers(seq: &[u8], layers: &[SequenceLayer]) -> Vec<Vec<u8>> {
+    let mut sequences = vec![seq.to_vec()];
+
+    layers.iter().for_each(|layer| {
+        let last = sequences.last().unwrap();
+        let mut prev = b'A';
+
+        let next = last
+            .iter()
+            .flat_map(|&button| {
+                let (_, presses) = layer
+                    .moves
+                    .iter()
+                    .find(|(pair, _)| *pair == (prev, button))
+                    .unwrap();
+                prev = button;
+
+                presses.iter().copied()
+            })
+            .collect();
+
+        sequences.push(next);
+    });
+
+    sequences
+}
+
+/// Prints each layer in full if the user's sequence, the longest, is short
+/// enough, or as the presses of each of its moves otherwise.
+fn print_sequence_layers(
+    seq: &[u8],
+    layers: &[SequenceLayer],
+    user_len: usize,
+) {
+    let sequences = if user_len <= MAX_EXPANDED_LEN {
+        Some(expand_layers(seq, layers))
+    } else {
+        None
+    };
+
+    println!("{}", format_ascii(seq));
+
+    layers.iter().enumerate().for_each(|(i, layer)| {
+        println!("  {} ({} presses)", layer.name, layer.len);
+
+        match &sequences {
+            // The first pad's sequence is `seq`, printed above.
+            Some(_) if i == 0 => {}
+            Some(sequences) => {
+                println!("    {}", format_run_length(&sequences[i]))
+            }
+            None => layer.moves.iter().for_each(|&((k1, k2), ref presses)| {
+                println!(
+                    "    {}{}: {}",
+                    k1 as char,
+                    k2 as char,
+                    format_run_length(presses)
+                );
+            }),
+        }
+    });
+
+    if let Some(user_sequence) = sequences.as_ref().and_then(|s| s.last()) {
+        println!("  User ({} presses)", user_sequence.len());
+        println!("    {}", format_run_length(user_sequence));
+    }
+}
+
+fn print_replay(numpad: &Pad, presses: &[u8]) {
+    match numpad.replay(presses) {
+        Ok(output) => println!("{}", format_ascii(&output)),
+        Err(error) => println!("{}", error),
+    }
+}
+
+/// Prints the sum of the complexities of the codes typed on the numpad
+/// through `num_dirpads` robot-operated dirpads. Codes without leading digits
+/// have no complexity, so their shortest sequence length is printed instead.
+pub fn print_complexities_sum(num_dirpads: usize) {
+    let data = fs::read_to_string("data/day_21.txt").expect("missing file");
+
+    let codes = data
+        .split('\n')
+        .take_while(|line| !line.is_empty())
+        .map(|line| {
+            let num_digits =
+                line.bytes().take_while(u8::is_ascii_digit).count();
+
+            Code {
+                num: line[..num_digits].parse::<usize>().ok(),
+                buttons: line.as_bytes(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let layouts = LAYOUTS
+        .map(|path| fs::read_to_string(path).expect("missing file"))
+        .unwrap_or_else(|| format!("{}\n\n{}", NUMPAD_LAYOUT, DIRPAD_LAYOUT));
+    let (numpad_layout, dirpad_layout) =
+        layouts.split_once("\n\n").expect("missing dirpad layout");
+
+    let mut numpad = Pad::new_chain(
+        num_dirpads,
+        &parse_layout(numpad_layout),
+        &parse_layout(dirpad_layout),
+    );
+
+    if let Some(path) = REPLAY {
+        let replay = fs::read_to_string(path).expect("missing file");
+
+        replay
+            .lines()
+            .filter(|line| !line.is_empty())
+            .for_each(|line| print_replay(&numpad, line.as_bytes()));
+
+        return;
+    }
+
+    let complexities = codes
+        .iter()
+        .filter_map(|Code { num, buttons }| {
+            let shortest_sequence = numpad.get_sequence_min(buttons).unwrap();
+
+            if SHOW_SEQUENCES {
+                let layers = numpad.get_sequence_layers(buttons).unwrap();
+                print_sequence_layers(buttons, &layers, shortest_sequence);
+
+                if shortest_sequence <= MAX_EXPANDED_LEN {
+                    let sequences = expand_layers(buttons, &layers);
+
+                    print!("  Replay ");
+                    print_replay(&numpad, sequences.last().unwrap());
+                }
+            }
+
+            if num.is_none() {
+                println!("{}: {}", format_ascii(buttons), shortest_sequence);
+            }
+
+            num.map(|num| shortest_sequence * num)
+        })
+        .collect::<Vec<_>>();
+
+    if !complexities.is_empty() {
+        println!("{}", complexities.iter().sum::<usize>());
+    }
+}
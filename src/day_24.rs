@@ -0,0 +1,1049 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+/// Holds the value of a bus. A product needs as many output bits as both
+/// inputs together, so circuits with bus bits past 127 are rejected.
+pub type Value = u128;
+
+const VERILOG_KEYWORDS: [&str; 17] = [
+    "and",
+    "assign",
+    "begin",
+    "buf",
+    "end",
+    "endmodule",
+    "inout",
+    "input",
+    "module",
+    "nand",
+    "nor",
+    "not",
+    "or",
+    "output",
+    "wire",
+    "xnor",
+    "xor",
+];
+
+/// Circuit file formats.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// The puzzle input: `x00: 1` wire values, then `a AND b -> c` gates.
+    Aoc,
+    /// Berkeley Logic Interchange Format, one `.names` cover per gate.
+    Blif,
+    /// Structural Verilog, one `assign c = a & b;` per gate.
+    Verilog,
+}
+
+/// Functions of `x` and `y` a circuit can be checked against.
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Target {
+    Add,
+    Subtract,
+    And,
+    Multiply,
+}
+
+impl Target {
+    pub fn apply(&self, x: Value, y: Value) -> Value {
+        match self {
+            Target::Add => x.wrapping_add(y),
+            Target::Subtract => x.wrapping_sub(y),
+            Target::And => x & y,
+            Target::Multiply => x.wrapping_mul(y),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Operation {
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Xnor,
+    /// Unary, its gates read the same wire as both inputs.
+    Not,
+}
+
+impl Operation {
+    const ALL: [Operation; 7] = [
+        Operation::And,
+        Operation::Or,
+        Operation::Xor,
+        Operation::Nand,
+        Operation::Nor,
+        Operation::Xnor,
+        Operation::Not,
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.name() == name)
+    }
+
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.symbol() == Some(symbol))
+    }
+
+    /// Bit `(val1 << 1) | val2` holds the output for that pair of inputs, or
+    /// bit `val1` for unary operations.
+    fn from_truth_table(table: u8, arity: usize) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|op| op.arity() == arity && op.truth_table() == table)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Operation::And => "AND",
+            Operation::Or => "OR",
+            Operation::Xor => "XOR",
+            Operation::Nand => "NAND",
+            Operation::Nor => "NOR",
+            Operation::Xnor => "XNOR",
+            Operation::Not => "NOT",
+        }
+    }
+
+    /// Verilog operator of the binary operations that have one.
+    fn symbol(&self) -> Option<&'static str> {
+        match self {
+            Operation::And => Some("&"),
+            Operation::Or => Some("|"),
+            Operation::Xor => Some("^"),
+            _ => None,
+        }
+    }
+
+    fn negated(&self) -> Option<Self> {
+        match self {
+            Operation::And => Some(Operation::Nand),
+            Operation::Or => Some(Operation::Nor),
+            Operation::Xor => Some(Operation::Xnor),
+            Operation::Nand => Some(Operation::And),
+            Operation::Nor => Some(Operation::Or),
+            Operation::Xnor => Some(Operation::Xor),
+            Operation::Not => None,
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Operation::Not => 1,
+            _ => 2,
+        }
+    }
+
+    fn truth_table(&self) -> u8 {
+        let arity = self.arity();
+
+        (0..1 << arity).fold(0, |acc, i| {
+            if self.eval((i >> (arity - 1)) & 1 != 0, i & 1 != 0) {
+                acc | (1 << i)
+            } else {
+                acc
+            }
+        })
+    }
+
+    fn eval(&self, val1: bool, val2: bool) -> bool {
+        match self {
+            Operation::And => val1 & val2,
+            Operation::Or => val1 | val2,
+            Operation::Xor => val1 ^ val2,
+            Operation::Nand => !(val1 & val2),
+            Operation::Nor => !(val1 | val2),
+            Operation::Xnor => !(val1 ^ val2),
+            Operation::Not => !val1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Diagnostic<'a> {
+    /// Gate outputs forming a combinational loop, sorted by name.
+    Cycle(Vec<&'a str>),
+    /// A wire read by a gate that is neither an input nor a gate output.
+    Undriven(&'a str),
+    /// A wire set by more than one gate, or by a gate and the inputs.
+    MultipleDrivers(&'a str, usize),
+}
+
+impl Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::Cycle(wires) => {
+                write!(f, "cycle through {}", wires.join(", "))
+            }
+            Diagnostic::Undriven(wire) => {
+                write!(f, "{wire} is read but never driven")
+            }
+            Diagnostic::MultipleDrivers(wire, count) => {
+                write!(f, "{wire} has {count} drivers")
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Gate<'a> {
+    pub in1: &'a str,
+    pub in2: &'a str,
+    pub op: Operation,
+    pub out: &'a str,
+}
+
+impl<'a> Gate<'a> {
+    pub fn new(
+        in1: &'a str,
+        op: Operation,
+        in2: &'a str,
+        out: &'a str,
+    ) -> Self {
+        let (min, max) = if in1 < in2 { (in1, in2) } else { (in2, in1) };
+
+        Self {
+            in1: min,
+            in2: max,
+            op,
+            out,
+        }
+    }
+
+    fn new_unary(in1: &'a str, op: Operation, out: &'a str) -> Self {
+        Self::new(in1, op, in1, out)
+    }
+
+    pub fn apply(&self, val1: bool, val2: bool) -> bool {
+        self.op.eval(val1, val2)
+    }
+
+    fn to_aoc(&self) -> String {
+        match self.op.arity() {
+            1 => format!("{} {} -> {}", self.op.name(), self.in1, self.out),
+            _ => format!(
+                "{} {} {} -> {}",
+                self.in1,
+                self.op.name(),
+                self.in2,
+                self.out
+            ),
+        }
+    }
+
+    fn to_verilog_expr(&self) -> String {
+        let in1 = verilog_name(self.in1);
+        let in2 = verilog_name(self.in2);
+
+        match (self.op.symbol(), self.op.negated()) {
+            (Some(symbol), _) => format!("{in1} {symbol} {in2}"),
+            (None, Some(op)) => {
+                format!("~({in1} {} {in2})", op.symbol().unwrap())
+            }
+            (None, None) => format!("~{in1}"),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Circuit<'a> {
+    wire_values: HashMap<&'a str, bool>,
+    /// Wires declared as inputs, whatever their names.
+    inputs: HashSet<&'a str>,
+    pub gates: Vec<Gate<'a>>,
+    pub num_input_bits: usize,
+    pub num_output_bits: usize,
+    pub output: Value,
+    pub target: Target,
+}
+
+impl<'a> Circuit<'a> {
+    pub fn new(
+        wire_values: HashMap<&'a str, bool>,
+        inputs: HashSet<&'a str>,
+        gates: Vec<Gate<'a>>,
+    ) -> Self {
+        let num_input_bits = wire_values
+            .iter()
+            .filter(|(&wire, _)| bus_bit(wire, 'x').is_some())
+            .count();
+        let num_output_bits = gates
+            .iter()
+            .filter(|gate| bus_bit(gate.out, 'z').is_some())
+            .count();
+
+        Self {
+            wire_values,
+            inputs,
+            gates,
+            num_input_bits,
+            num_output_bits,
+            output: 0,
+            target: Target::Add,
+        }
+    }
+
+    pub fn parse(src: &'a str, format: Format) -> Option<Self> {
+        let circuit = match format {
+            Format::Aoc => Self::parse_aoc(src),
+            Format::Blif => Self::parse_blif(src),
+            Format::Verilog => Self::parse_verilog(src),
+        }?;
+
+        let fits_value = circuit
+            .wire_values
+            .keys()
+            .flat_map(|wire| [bus_bit(wire, 'x'), bus_bit(wire, 'y')])
+            .chain(circuit.gates.iter().map(|gate| bus_bit(gate.out, 'z')))
+            .flatten()
+            .all(|bit| bit < Value::BITS);
+
+        fits_value.then_some(circuit)
+    }
+
+    fn parse_aoc(src: &'a str) -> Option<Self> {
+        let mut lines = src.split('\n');
+
+        let wire_values = lines
+            .by_ref()
+            .take_while(|line| !line.is_empty())
+            .map(parse_wire_value)
+            .collect::<Option<HashMap<_, _>>>()?;
+
+        let gates = lines
+            .take_while(|line| !line.is_empty())
+            .map(|line| {
+                let parts = line.splitn(5, " ").collect::<Vec<_>>();
+
+                match parts[..] {
+                    [op, in1, "->", out] => Some(Gate::new_unary(
+                        in1,
+                        Operation::from_name(op)?,
+                        out,
+                    )),
+                    [in1, op, in2, "->", out] => Some(Gate::new(
+                        in1,
+                        Operation::from_name(op)?,
+                        in2,
+                        out,
+                    )),
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let inputs = wire_values.keys().copied().collect();
+
+        Some(Self::new(wire_values, inputs, gates))
+    }
+
+    /// Input values are not part of BLIF, so they are carried in `# x00: 1`
+    /// comments. Inputs without one default to 0.
+    fn parse_blif(src: &'a str) -> Option<Self> {
+        let mut wire_values = HashMap::new();
+        let mut inputs = HashSet::new();
+        let mut gates = Vec::new();
+        // Inputs and output of the `.names` being read, and its on-set and
+        // off-set truth tables.
+        let mut cover: Option<(Vec<&str>, u8, u8)> = None;
+
+        let finish_cover = |cover: Option<(Vec<&'a str>, u8, u8)>,
+                            gates: &mut Vec<Gate<'a>>|
+         -> Option<()> {
+            if let Some((names, on_set, off_set)) = cover {
+                let arity = names.len().checked_sub(1)?;
+                let table = if off_set != 0 {
+                    !off_set & ((1 << (1 << arity)) - 1)
+                } else {
+                    on_set
+                };
+                let op = Operation::from_truth_table(table, arity)?;
+
+                match names[..] {
+                    [in1, out] => gates.push(Gate::new_unary(in1, op, out)),
+                    [in1, in2, out] => gates.push(Gate::new(in1, op, in2, out)),
+                    _ => return None,
+                }
+            }
+
+            Some(())
+        };
+
+        let (lines, comments) = tokenize_blif(src);
+
+        comments
+            .iter()
+            .filter_map(|comment| parse_wire_value(comment.trim()))
+            .for_each(|(wire, value)| {
+                wire_values.insert(wire, value);
+            });
+
+        for words in lines {
+            match words[..] {
+                [] => {}
+                [".names", ref names @ ..] => {
+                    finish_cover(cover.take(), &mut gates)?;
+                    cover = Some((names.to_vec(), 0, 0));
+                }
+                [".inputs", ref names @ ..] => {
+                    finish_cover(cover.take(), &mut gates)?;
+                    inputs.extend(names.iter().copied());
+                }
+                [".model" | ".outputs" | ".end", ..] => {
+                    finish_cover(cover.take(), &mut gates)?;
+                }
+                [row, output] => {
+                    let (names, on_set, off_set) = cover.as_mut()?;
+                    let arity = row.len();
+
+                    if arity + 1 != names.len() || arity > 2 {
+                        return None;
+                    }
+
+                    (0..1u8 << arity)
+                        .filter(|i| {
+                            row.bytes().enumerate().all(|(j, bit)| {
+                                let value = (i >> (arity - 1 - j)) & 1;
+
+                                match bit {
+                                    b'0' => value == 0,
+                                    b'1' => value != 0,
+                                    _ => bit == b'-',
+                                }
+                            })
+                        })
+                        .for_each(|i| match output {
+                            "0" => *off_set |= 1 << i,
+                            _ => *on_set |= 1 << i,
+                        });
+                }
+                _ => return None,
+            }
+        }
+
+        finish_cover(cover, &mut gates)?;
+
+        // Values given in comments for wires that are not inputs are ignored.
+        wire_values.retain(|wire, _| inputs.contains(wire));
+        inputs.iter().for_each(|wire| {
+            wire_values.entry(wire).or_insert(false);
+        });
+
+        Some(Self::new(wire_values, inputs, gates))
+    }
+
+    /// Input values are not part of Verilog, so they are carried in
+    /// `// x00: 1` comments. Inputs without one default to 0. Only `input`
+    /// declarations and `assign` statements of a single operator, optionally
+    /// negated with `~`, are interpreted; the remaining declarations are
+    /// skipped.
+    fn parse_verilog(src: &'a str) -> Option<Self> {
+        let mut wire_values = HashMap::new();
+        let mut inputs = HashSet::new();
+        let mut gates = Vec::new();
+
+        let (tokens, comments) = tokenize_verilog(src);
+
+        comments
+            .iter()
+            .filter_map(|comment| parse_wire_value(comment.trim()))
+            .for_each(|(wire, value)| {
+                wire_values.insert(wire, value);
+            });
+
+        for statement in tokens.split(|&token| token == ";") {
+            match statement {
+                ["input", names @ ..] => inputs
+                    .extend(names.iter().copied().filter(|&name| name != ",")),
+                ["assign", out, "=", "~", in1] => {
+                    gates.push(Gate::new_unary(in1, Operation::Not, out))
+                }
+                ["assign", out, "=", in1, op, in2] => gates.push(Gate::new(
+                    in1,
+                    Operation::from_symbol(op)?,
+                    in2,
+                    out,
+                )),
+                ["assign", out, "=", "~", "(", in1, op, in2, ")"] => gates
+                    .push(Gate::new(
+                        in1,
+                        Operation::from_symbol(op)?.negated()?,
+                        in2,
+                        out,
+                    )),
+                ["assign", out, "=", in1, "~", "^", in2]
+                | ["assign", out, "=", in1, "^", "~", in2] => {
+                    gates.push(Gate::new(in1, Operation::Xnor, in2, out))
+                }
+                ["assign", ..] => return None,
+                _ => {}
+            }
+        }
+
+        wire_values.retain(|wire, _| inputs.contains(wire));
+        inputs.iter().for_each(|wire| {
+            wire_values.entry(wire).or_insert(false);
+        });
+
+        Some(Self::new(wire_values, inputs, gates))
+    }
+
+    fn input_wires(&self) -> Vec<&'a str> {
+        let mut wires = self.inputs.iter().copied().collect::<Vec<_>>();
+        wires.sort();
+        wires
+    }
+
+    pub fn output_wires(&self) -> Vec<&'a str> {
+        let mut wires = self
+            .gates
+            .iter()
+            .map(|gate| gate.out)
+            .filter(|wire| bus_bit(wire, 'z').is_some())
+            .collect::<Vec<_>>();
+        wires.sort();
+        wires
+    }
+
+    fn internal_wires(&self) -> Vec<&'a str> {
+        let mut wires = self
+            .gates
+            .iter()
+            .map(|gate| gate.out)
+            .filter(|wire| bus_bit(wire, 'z').is_none())
+            .collect::<Vec<_>>();
+        wires.sort();
+        wires
+    }
+
+    pub fn to_format(&self, format: Format) -> String {
+        match format {
+            Format::Aoc => self.to_aoc(),
+            Format::Blif => self.to_blif(),
+            Format::Verilog => self.to_verilog(),
+        }
+    }
+
+    fn to_aoc(&self) -> String {
+        let values = self
+            .input_wires()
+            .iter()
+            .map(|wire| format!("{wire}: {}\n", self.wire_values[wire] as u8))
+            .collect::<String>();
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| gate.to_aoc() + "\n")
+            .collect::<String>();
+
+        format!("{values}\n{gates}")
+    }
+
+    fn to_blif(&self) -> String {
+        let inputs = self.input_wires();
+        let values = inputs
+            .iter()
+            .map(|wire| format!("# {wire}: {}\n", self.wire_values[wire] as u8))
+            .collect::<String>();
+        let gates = self
+            .gates
+            .iter()
+            .map(|gate| {
+                let arity = gate.op.arity();
+                let table = gate.op.truth_table();
+                let rows = (0..1 << arity)
+                    .filter(|i| table & (1 << i) != 0)
+                    .map(|i| format!("{:0arity$b} 1\n", i))
+                    .collect::<String>();
+                let inputs = [gate.in1, gate.in2][..arity].join(" ");
+
+                format!(".names {inputs} {}\n{rows}", gate.out)
+            })
+            .collect::<String>();
+
+        format!(
+            "{values}.model day24\n.inputs {}\n.outputs {}\n{gates}.end\n",
+            inputs.join(" "),
+            self.output_wires().join(" ")
+        )
+    }
+
+    fn to_verilog(&self) -> String {
+        let inputs = self.input_wires();
+        let outputs = self.output_wires();
+        let values = inputs
+            .iter()
+            .map(|wire| {
+                format!("// {wire}: {}\n", self.wire_values[wire] as u8)
+            })
+            .collect::<String>();
+        let ports = inputs
+            .iter()
+            .chain(outputs.iter())
+            .map(|&wire| format!("  {}", verilog_name(wire)))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let declarations = [
+            ("input", inputs),
+            ("output", outputs),
+            ("wire", self.internal_wires()),
+        ]
+        .iter()
+        .flat_map(|(kind, wires)| {
+            wires
+                .iter()
+                .map(move |&wire| format!("  {kind} {};\n", verilog_name(wire)))
+        })
+        .collect::<String>();
+        let assigns = self
+            .gates
+            .iter()
+            .map(|gate| {
+                format!(
+                    "  assign {} = {};\n",
+                    verilog_name(gate.out),
+                    gate.to_verilog_expr()
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            "{values}module day24 (\n{ports}\n);\n{declarations}{assigns}endmodule\n"
+        )
+    }
+
+    fn wires_to_values(&self, set: char) -> Value {
+        self.wire_values
+            .iter()
+            .filter_map(|(&wire, &value)| Some((bus_bit(wire, set)?, value)))
+            .fold(
+                0,
+                |acc, (bit, value)| if value { acc + (1 << bit) } else { acc },
+            )
+    }
+
+    pub fn bad_bits(&self) -> Value {
+        self.expected_output() ^ self.output
+    }
+
+    fn set_wire_value(&mut self, set: char, new_value: Value) {
+        self.wire_values
+            .iter_mut()
+            .filter_map(|(&wire, value)| Some((bus_bit(wire, set)?, value)))
+            .for_each(|(bit, value)| *value = new_value & (1 << bit) != 0);
+    }
+
+    fn expected_output(&self) -> Value {
+        let output = self
+            .target
+            .apply(self.wires_to_values('x'), self.wires_to_values('y'));
+
+        output & low_bits(self.num_output_bits)
+    }
+
+    // WARNING: Do NOT run twice in a row. Wire values are not reset.
+    pub fn run(&mut self) -> bool {
+        let mut remaining_gates = self.gates.clone();
+
+        while !remaining_gates.is_empty() {
+            let num_remaining_gates = remaining_gates.len();
+
+            remaining_gates.retain(|gate| {
+                match (
+                    self.wire_values.get(gate.in1),
+                    self.wire_values.get(gate.in2),
+                ) {
+                    (Some(&val1), Some(&val2)) => {
+                        self.wire_values
+                            .insert(gate.out, gate.apply(val1, val2));
+                        false
+                    }
+
+                    _ => true,
+                }
+            });
+
+            // Infinite loop, see `diagnose` for the cause.
+            if remaining_gates.len() == num_remaining_gates {
+                return false;
+            }
+        }
+
+        self.output = self.wires_to_values('z');
+
+        true
+    }
+
+    fn run_with_values(&mut self, x: Value, y: Value) -> bool {
+        self.set_wire_value('x', x);
+        self.set_wire_value('y', y);
+        self.run()
+    }
+
+    /// Runs the circuit on patterned inputs and returns the output bits that
+    /// differ from the target on any of them, or `None` if it cannot settle.
+    pub fn verify(&self) -> Option<Value> {
+        let all_bits = low_bits(self.num_input_bits);
+        let low_bits = low_bits(self.num_input_bits / 2);
+        let high_bits = low_bits << self.num_input_bits.div_ceil(2);
+        let alternating_bits = (0..self.num_input_bits)
+            .step_by(2)
+            .fold(0, |acc, i| acc + (1 << i));
+
+        [
+            all_bits,
+            low_bits,
+            high_bits,
+            alternating_bits,
+            alternating_bits >> 1,
+        ]
+        .iter()
+        .flat_map(|&bits| [(bits, 1), (1, bits), (bits, bits)])
+        .try_fold(0, |acc, (x, y)| {
+            let mut test = self.clone();
+
+            test.run_with_values(x, y).then(|| acc | test.bad_bits())
+        })
+    }
+
+    /// Outputs of the gates that `wires` depend on, the wires included.
+    pub fn fan_in(&self, wires: &[&'a str]) -> HashSet<&'a str> {
+        let mut fan_in = HashSet::new();
+        let mut remaining = wires.to_vec();
+
+        while let Some(wire) = remaining.pop() {
+            if let Some(gate) = self.gates.iter().find(|gate| gate.out == wire)
+            {
+                if fan_in.insert(wire) {
+                    remaining.extend([gate.in1, gate.in2]);
+                }
+            }
+        }
+
+        fan_in
+    }
+
+    pub fn print_gates(&self, wire: Option<&str>) {
+        let mut seen = HashSet::new();
+
+        let mut remaining = self
+            .gates
+            .iter()
+            .filter(|gate| match wire {
+                Some(wire) => gate.out == wire,
+                _ => bus_bit(gate.out, 'z').is_some(),
+            })
+            .map(|gate| (gate.out, 0))
+            .collect::<Vec<_>>();
+        remaining.sort_by(|a, b| b.0.cmp(a.0));
+
+        while let Some((wire, depth)) = remaining.pop() {
+            if seen.contains(&wire) {
+                println!("{:depth$}{wire}  (seen)", "",);
+            } else if let Some(gate) =
+                self.gates.iter().find(|gate| gate.out == wire)
+            {
+                println!(
+                    "{:depth$}{wire}  {} {:?} {}",
+                    "", gate.in1, gate.op, gate.in2
+                );
+                remaining.push((gate.in2, depth + 1));
+                remaining.push((gate.in1, depth + 1));
+
+                seen.insert(wire);
+            }
+        }
+
+        println!();
+    }
+
+    /// Swaps the outputs of two gates and returns the problems of the
+    /// resulting circuit, empty if it can run.
+    pub fn swap_gates(
+        &mut self,
+        gate_a: &str,
+        gate_b: &str,
+    ) -> Vec<Diagnostic<'a>> {
+        let gate_idx_a = self.gates.iter().position(|g| g.out == gate_a);
+        let gate_idx_b = self.gates.iter().position(|g| g.out == gate_b);
+
+        if let (Some(gate_idx_a), Some(gate_idx_b)) = (gate_idx_a, gate_idx_b) {
+            (self.gates[gate_idx_a].out, self.gates[gate_idx_b].out) =
+                (self.gates[gate_idx_b].out, self.gates[gate_idx_a].out);
+        }
+
+        self.diagnose()
+    }
+
+    /// Finds what keeps `run` from settling: combinational loops, wires that
+    /// are read but never driven, and wires with more than one driver.
+    pub fn diagnose(&self) -> Vec<Diagnostic<'a>> {
+        let mut drivers: HashMap<&str, Vec<usize>> = HashMap::new();
+        self.gates.iter().enumerate().for_each(|(idx, gate)| {
+            drivers.entry(gate.out).or_default().push(idx);
+        });
+
+        // A declared input counts as one more driver of its wire.
+        let mut multiple_drivers = drivers
+            .iter()
+            .map(|(&wire, idxs)| {
+                (wire, idxs.len() + self.inputs.contains(wire) as usize)
+            })
+            .filter(|&(_, count)| count > 1)
+            .map(|(wire, count)| Diagnostic::MultipleDrivers(wire, count))
+            .collect::<Vec<_>>();
+        multiple_drivers.sort_by_key(|diagnostic| diagnostic.to_string());
+
+        let mut undriven = self
+            .gates
+            .iter()
+            .flat_map(|gate| [gate.in1, gate.in2])
+            .filter(|&wire| {
+                !self.inputs.contains(wire) && !drivers.contains_key(wire)
+            })
+            .collect::<Vec<_>>();
+        undriven.sort();
+        undriven.dedup();
+
+        let successors = self
+            .gates
+            .iter()
+            .map(|gate| {
+                self.gates
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, g)| g.in1 == gate.out || g.in2 == gate.out)
+                    .map(|(idx, _)| idx)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut cycles = strongly_connected_components(&successors)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || successors[component[0]].contains(&component[0])
+            })
+            .map(|component| {
+                let mut wires = component
+                    .iter()
+                    .map(|&idx| self.gates[idx].out)
+                    .collect::<Vec<_>>();
+                wires.sort();
+                wires.dedup();
+                Diagnostic::Cycle(wires)
+            })
+            .collect::<Vec<_>>();
+        cycles.sort_by_key(|diagnostic| diagnostic.to_string());
+
+        cycles
+            .into_iter()
+            .chain(undriven.into_iter().map(Diagnostic::Undriven))
+            .chain(multiple_drivers)
+            .collect()
+    }
+}
+
+impl Debug for Circuit<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "in: {}, out: {}\nin1:  {:#0in_width$b}\nin2:  {:#0in_width$b}\nout: {:#0out_width$b}\nact: {:#0out_width$b}\ndif: {:#0out_width$b}",
+            self.num_input_bits,
+            self.num_output_bits,
+            self.wires_to_values('x'),
+            self.wires_to_values('y'),
+            self.expected_output(),
+            self.output,
+            self.bad_bits(),
+            // +2 to account for "0b"
+            in_width = self.num_input_bits + 2,
+            out_width = self.num_output_bits + 2
+        )
+    }
+}
+
+/// Bit of a bus wire such as `x05` of the bus `set`, or `None` for any other
+/// wire.
+pub fn bus_bit(wire: &str, set: char) -> Option<u32> {
+    wire.strip_prefix(set)?.parse().ok()
+}
+
+/// A value with the lowest `num_bits` bits set.
+fn low_bits(num_bits: usize) -> Value {
+    u32::try_from(num_bits)
+        .ok()
+        .and_then(|num_bits| Value::checked_shl(1, num_bits))
+        .map_or(Value::MAX, |bit| bit - 1)
+}
+
+fn parse_wire_value(src: &str) -> Option<(&str, bool)> {
+    src.split_once(": ")
+        .and_then(|(wire, value)| Some((wire, value.parse::<u8>().ok()? != 0)))
+}
+
+/// Splits BLIF into the words of each logical line, following `\` line
+/// continuations, and collects the `#` comments separately.
+fn tokenize_blif(src: &str) -> (Vec<Vec<&str>>, Vec<&str>) {
+    let mut lines: Vec<Vec<&str>> = Vec::new();
+    let mut comments = Vec::new();
+    let mut is_continued = false;
+
+    for line in src.lines() {
+        let line = match line.split_once('#') {
+            Some((line, comment)) => {
+                comments.push(comment);
+                line
+            }
+            None => line,
+        };
+
+        let line = line.trim_end();
+        let (line, is_continuing) = match line.strip_suffix('\\') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+
+        let words = line.split_whitespace();
+
+        match lines.last_mut() {
+            Some(last) if is_continued => last.extend(words),
+            _ => lines.push(words.collect()),
+        }
+
+        is_continued = is_continuing;
+    }
+
+    (lines, comments)
+}
+
+fn verilog_name(wire: &str) -> String {
+    if VERILOG_KEYWORDS.contains(&wire) {
+        // Escaped identifiers end at the next whitespace.
+        format!("\\{wire} ")
+    } else {
+        wire.to_string()
+    }
+}
+
+/// Splits Verilog into identifier and punctuation tokens, and collects the
+/// `//` comments separately.
+fn tokenize_verilog(src: &str) -> (Vec<&str>, Vec<&str>) {
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let bytes = src.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let start = i;
+
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = src[i..].find('\n').map_or(bytes.len(), |end| i + end);
+                comments.push(&src[start + 2..i]);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = src[i..].find("*/").map_or(bytes.len(), |end| i + end + 2);
+            }
+            b'\\' => {
+                i = src[i..]
+                    .find(char::is_whitespace)
+                    .map_or(bytes.len(), |end| i + end);
+                tokens.push(&src[start + 1..i]);
+            }
+            byte if byte.is_ascii_whitespace() => i += 1,
+            byte if byte.is_ascii_alphanumeric() || byte == b'_' => {
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric()
+                        || bytes[i] == b'_'
+                        || bytes[i] == b'$')
+                {
+                    i += 1;
+                }
+                tokens.push(&src[start..i]);
+            }
+            _ => {
+                i += 1;
+                tokens.push(&src[start..i]);
+            }
+        }
+    }
+
+    (tokens, comments)
+}
+
+/// Tarjan's algorithm over the vertices `0..successors.len()`.
+fn strongly_connected_components(successors: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct State<'a> {
+        successors: &'a [Vec<usize>],
+        next_index: usize,
+        index: Vec<Option<usize>>,
+        low_link: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        components: Vec<Vec<usize>>,
+    }
+
+    fn visit(state: &mut State, vertex: usize) {
+        state.index[vertex] = Some(state.next_index);
+        state.low_link[vertex] = state.next_index;
+        state.next_index += 1;
+        state.stack.push(vertex);
+        state.on_stack[vertex] = true;
+
+        for &next in state.successors[vertex].iter() {
+            match state.index[next] {
+                None => {
+                    visit(state, next);
+                    state.low_link[vertex] =
+                        state.low_link[vertex].min(state.low_link[next]);
+                }
+                Some(next_index) if state.on_stack[next] => {
+                    state.low_link[vertex] =
+                        state.low_link[vertex].min(next_index);
+                }
+                _ => {}
+            }
+        }
+
+        if Some(state.low_link[vertex]) == state.index[vertex] {
+            let mut component = Vec::new();
+
+            while let Some(v) = state.stack.pop() {
+                state.on_stack[v] = false;
+                component.push(v);
+
+                if v == vertex {
+                    break;
+                }
+            }
+
+            state.components.push(component);
+        }
+    }
+
+    let num_vertices = successors.len();
+    let mut state = State {
+        successors,
+        next_index: 0,
+        index: vec![None; num_vertices],
+        low_link: vec![0; num_vertices],
+        stack: Vec::new(),
+        on_stack: vec![false; num_vertices],
+        components: Vec::new(),
+    };
+
+    (0..num_vertices).for_each(|vertex| {
+        if state.index[vertex].is_none() {
+            visit(&mut state, vertex);
+        }
+    });
+
+    state.components
+}
@@ -1,273 +1,150 @@
-use std::{
-    collections::{HashMap, HashSet},
-    fmt::Debug,
-    fs,
-    hash::Hash,
-};
+use std::fs;
 
-const DEBUG: bool = false;
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-enum Operation {
-    And,
-    Or,
-    Xor,
-}
-
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Gate<'a> {
-    in1: &'a str,
-    in2: &'a str,
-    op: Operation,
-    out: &'a str,
-}
-
-impl<'a> Gate<'a> {
-    fn new(in1: &'a str, op: Operation, in2: &'a str, out: &'a str) -> Self {
-        let (min, max) = if in1 < in2 { (in1, in2) } else { (in2, in1) };
+use crate::day_24::{bus_bit, Circuit, Format, Operation, Target};
 
-        Self {
-            in1: min,
-            in2: max,
-            op,
-            out,
-        }
+const DEBUG: bool = false;
+/// File the circuit is read from, in the given format.
+const INPUT: (&str, Format) = ("data/day_24.txt", Format::Aoc);
+/// Writes the circuit as read, for instance in another format.
+const EXPORT: Option<(&str, Format)> = None;
+/// Writes each repaired circuit the `DEBUG` swap search finds.
+const EXPORT_REPAIRED: Option<(&str, Format)> = None;
+/// Function of `x` and `y` that the `z` outputs should compute.
+const TARGET: Target = Target::Add;
+/// Most gate pairs swapped when searching repairs for targets other than
+/// `Add`, under `DEBUG`.
+const MAX_SWAPS: usize = 4;
+
+/// Every way to pick `num_pairs` disjoint pairs out of `list`.
+fn get_pairs_from_set<'a>(
+    list: &[&'a str],
+    num_pairs: usize,
+) -> Vec<Vec<(&'a str, &'a str)>> {
+    if num_pairs == 0 {
+        return vec![Vec::new()];
     }
 
-    fn apply(&self, val1: bool, val2: bool) -> bool {
-        match self.op {
-            Operation::And => val1 & val2,
-            Operation::Or => val1 | val2,
-            Operation::Xor => val1 ^ val2,
-        }
+    if list.len() < num_pairs * 2 {
+        return Vec::new();
     }
-}
 
-#[derive(Clone)]
-struct Circuit<'a> {
-    wire_values: HashMap<&'a str, bool>,
-    gates: Vec<Gate<'a>>,
-    num_input_bits: usize,
-    num_output_bits: usize,
-    output: u64,
-}
+    // Sets without the first item, then sets pairing it with each other one.
+    let mut result = get_pairs_from_set(&list[1..], num_pairs);
 
-impl<'a> Circuit<'a> {
-    fn new(wire_values: HashMap<&'a str, bool>, gates: Vec<Gate<'a>>) -> Self {
-        let num_input_bits = wire_values
-            .iter()
-            .filter(|(&wire, _)| wire.starts_with('x'))
-            .count();
-        let num_output_bits = gates
-            .iter()
-            .filter(|gate| gate.out.starts_with('z'))
-            .count();
-
-        Self {
-            wire_values,
-            gates,
-            num_input_bits,
-            num_output_bits,
-            output: 0,
-        }
-    }
-
-    fn wires_to_values(&self, set: char) -> u64 {
-        self.wire_values
-            .iter()
-            .filter(|(&wire, _)| wire.starts_with(set))
-            .fold(0, |acc, (&wire, &value)| {
-                if value {
-                    acc + (1 << wire[1..].parse::<u8>().unwrap())
-                } else {
-                    acc
-                }
-            })
-    }
+    list[1..].iter().enumerate().for_each(|(idx, &item)| {
+        let mut remaining = list[1..].to_vec();
+        remaining.remove(idx);
 
-    fn bad_bits(&self) -> u64 {
-        self.expected_output() ^ self.output
-    }
+        result.extend(
+            get_pairs_from_set(&remaining, num_pairs - 1)
+                .into_iter()
+                .map(|mut pair_set| {
+                    pair_set.push((list[0], item));
+                    pair_set
+                }),
+        );
+    });
 
-    fn is_bits_ok(&self) -> bool {
-        self.bad_bits() == 0
-    }
+    result
+}
 
-    fn set_wire_value(&mut self, set: char, new_value: u64) {
-        self.wire_values
-            .iter_mut()
-            .filter(|(&wire, _)| wire.starts_with(set))
-            .for_each(|(&wire, value)| {
-                *value =
-                    new_value & (1 << wire[1..].parse::<u8>().unwrap()) != 0;
-            });
-    }
+/// Tries every set of `num_pairs` swaps among `candidates` and prints the
+/// ones that make the circuit pass verification. Returns whether there was
+/// one.
+fn search_swaps(
+    circuit: &Circuit,
+    candidates: &[&str],
+    num_pairs: usize,
+) -> bool {
+    let mut found = false;
+
+    get_pairs_from_set(candidates, num_pairs)
+        .iter()
+        .for_each(|pair_set| {
+            let mut swapped = circuit.clone();
+            let diagnostics = pair_set
+                .iter()
+                .fold(Vec::new(), |_, pair| swapped.swap_gates(pair.0, pair.1));
 
-    fn expected_output(&self) -> u64 {
-        self.wires_to_values('x') + self.wires_to_values('y')
-    }
+            if !diagnostics.is_empty() {
+                println!("broken pairs {:?}", pair_set);
+                diagnostics.iter().for_each(|diagnostic| {
+                    println!("  {}", diagnostic);
+                });
+                println!();
+                return;
+            }
 
-    // WARNING: Do NOT run twice in a row. Wire values are not reset.
-    fn run(&mut self) -> bool {
-        let mut remaining_gates = self.gates.clone();
-
-        while !remaining_gates.is_empty() {
-            let num_remaining_gates = remaining_gates.len();
-
-            remaining_gates.retain(|gate| {
-                match (
-                    self.wire_values.get(gate.in1),
-                    self.wire_values.get(gate.in2),
-                ) {
-                    (Some(&val1), Some(&val2)) => {
-                        self.wire_values
-                            .insert(gate.out, gate.apply(val1, val2));
-                        false
-                    }
+            if swapped.verify() == Some(0) {
+                println!("valid pairs {:?}\n", pair_set);
+                found = true;
 
-                    _ => true,
+                if let Some((path, format)) = EXPORT_REPAIRED {
+                    fs::write(path, swapped.to_format(format))
+                        .expect("cannot write file");
                 }
-            });
-
-            // Infinite loop
-            if remaining_gates.len() == num_remaining_gates {
-                return false;
             }
-        }
-
-        self.output = self.wires_to_values('z');
-
-        true
-    }
-
-    fn run_with_values(&mut self, x: u64, y: u64) -> bool {
-        self.set_wire_value('x', x);
-        self.set_wire_value('y', y);
-        self.run()
-    }
-
-    fn print_gates(&self, wire: Option<&str>) {
-        let mut seen = HashSet::new();
+        });
 
-        let mut remaining = self
-            .gates
-            .iter()
-            .filter(|gate| match wire {
-                Some(wire) => gate.out == wire,
-                _ => gate.out.starts_with('z'),
-            })
-            .map(|gate| (gate.out, 0))
-            .collect::<Vec<_>>();
-        remaining.sort_by(|a, b| b.0.cmp(a.0));
-
-        while let Some((wire, depth)) = remaining.pop() {
-            if seen.contains(&wire) {
-                println!("{:depth$}{wire}  (seen)", "",);
-            } else if let Some(gate) =
-                self.gates.iter().find(|gate| gate.out == wire)
-            {
-                println!(
-                    "{:depth$}{wire}  {} {:?} {}",
-                    "", gate.in1, gate.op, gate.in2
-                );
-                remaining.push((gate.in2, depth + 1));
-                remaining.push((gate.in1, depth + 1));
-
-                seen.insert(wire);
-            }
-        }
+    found
+}
 
-        println!();
-    }
+/// https://adventofcode.com/2024/day/24#part2
+pub fn day_24_2() {
+    let data = fs::read_to_string(INPUT.0).expect("missing file");
 
-    fn swap_gates(&mut self, gate_a: &str, gate_b: &str) {
-        let gate_idx_a = self.gates.iter().position(|g| g.out == gate_a);
-        let gate_idx_b = self.gates.iter().position(|g| g.out == gate_b);
+    let mut circuit = Circuit::parse(&data, INPUT.1).expect("invalid circuit");
+    circuit.target = TARGET;
 
-        if let (Some(gate_idx_a), Some(gate_idx_b)) = (gate_idx_a, gate_idx_b) {
-            (self.gates[gate_idx_a].out, self.gates[gate_idx_b].out) =
-                (self.gates[gate_idx_b].out, self.gates[gate_idx_a].out);
-        }
+    if let Some((path, format)) = EXPORT {
+        fs::write(path, circuit.to_format(format)).expect("cannot write file");
     }
-}
 
-impl Debug for Circuit<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(
-            f,
-            "in: {}, out: {}\nin1:  {:#0in_width$b}\nin2:  {:#0in_width$b}\nout: {:#0out_width$b}\nact: {:#0out_width$b}\ndif: {:#0out_width$b}",
-            self.num_input_bits,
-            self.num_output_bits,
-            self.wires_to_values('x'),
-            self.wires_to_values('y'),
-            self.expected_output(),
-            self.output,
-            self.bad_bits(),
-            // +2 to account for "0b"
-            in_width = self.num_input_bits + 2,
-            out_width = self.num_output_bits + 2
-        )
-    }
-}
+    circuit.diagnose().iter().for_each(|diagnostic| {
+        println!("{}", diagnostic);
+    });
 
-fn get_pairs_from_set(list: Vec<&str>) -> Vec<Vec<(&str, &str)>> {
-    if list.is_empty() {
-        return vec![Vec::new()];
+    if DEBUG {
+        circuit.print_gates(None);
     }
 
-    list[1..]
-        .iter()
-        .enumerate()
-        .flat_map(|(idx, item)| {
-            let mut remaining = list.clone();
-            remaining.remove(0);
-            remaining.remove(idx);
-
-            let mut result = get_pairs_from_set(remaining.clone());
-            result.iter_mut().for_each(|l| l.push((list[0], item)));
-
-            result
-        })
-        .collect()
-}
-
-/// https://adventofcode.com/2024/day/24#part2
-pub fn day_24_2() {
-    let data = fs::read_to_string("data/day_24.txt").expect("missing file");
-
-    let mut lines = data.split('\n');
+    // The swap heuristics below only know the ripple-carry adder layout, so
+    // other targets get the outputs that fail verification, and under `DEBUG`
+    // a search for swaps among the gates feeding them.
+    if TARGET != Target::Add {
+        let Some(bad_bits) = circuit.verify() else {
+            println!("circuit does not settle");
+            return;
+        };
+
+        let (bad_outputs, good_outputs): (Vec<_>, Vec<_>) =
+            circuit.output_wires().into_iter().partition(|wire| {
+                bus_bit(wire, 'z').is_some_and(|bit| bad_bits & (1 << bit) != 0)
+            });
 
-    let wire_values = lines
-        .by_ref()
-        .take_while(|line| !line.is_empty())
-        .filter_map(|line| {
-            line.split_once(": ")
-                .map(|(wire, value)| (wire, value.parse::<u8>().unwrap() != 0))
-        })
-        .collect::<HashMap<_, _>>();
-
-    let gates = lines
-        .take_while(|line| !line.is_empty())
-        .map(|line| {
-            let parts = line.splitn(5, " ").collect::<Vec<_>>();
-
-            Gate::new(
-                parts[0],
-                match parts[1] {
-                    "AND" => Operation::And,
-                    "OR" => Operation::Or,
-                    _ => Operation::Xor,
-                },
-                parts[2],
-                parts[4],
-            )
-        })
-        .collect::<Vec<_>>();
+        if DEBUG && !bad_outputs.is_empty() {
+            // Swapping a gate that a good output depends on would likely
+            // break that output.
+            let good_fan_in = circuit.fan_in(&good_outputs);
+            let mut candidates = circuit
+                .fan_in(&bad_outputs)
+                .into_iter()
+                .filter(|wire| !good_fan_in.contains(wire))
+                .collect::<Vec<_>>();
+            candidates.sort();
+
+            println!("swap candidates {:?}\n", candidates);
+
+            for num_pairs in 1..=MAX_SWAPS {
+                if search_swaps(&circuit, &candidates, num_pairs) {
+                    break;
+                }
+            }
+        }
 
-    let circuit = Circuit::new(wire_values, gates);
+        println!("{}", bad_outputs.join(","));
 
-    if DEBUG {
-        circuit.print_gates(None);
+        return;
     }
 
     // Bad output values.
@@ -275,12 +152,10 @@ pub fn day_24_2() {
         .gates
         .iter()
         .filter(|gate| {
-            let out = gate.out;
-
-            out.starts_with('z')
-                && gate.op != Operation::Xor
-                && out[1..].parse::<usize>().unwrap()
-                    < circuit.num_output_bits - 1
+            bus_bit(gate.out, 'z').is_some_and(|bit| {
+                gate.op != Operation::Xor
+                    && bit as usize + 1 < circuit.num_output_bits
+            })
         })
         .map(|gate| gate.out)
         .collect::<Vec<_>>();
@@ -292,16 +167,14 @@ pub fn day_24_2() {
     let bad_l2_gates = circuit
         .gates
         .iter()
-        .filter(|&gate| {
-            gate.out.starts_with('z')
-                && !bad_z_gates.contains(&gate.out)
-                && gate.out[1..]
-                    .parse::<usize>()
-                    .is_ok_and(|num| (2..circuit.num_input_bits).contains(&num))
-        })
-        .flat_map(|gate| {
-            let gate_num = &gate.out[1..];
+        .filter_map(|gate| {
+            let bit = bus_bit(gate.out, 'z')?;
 
+            (!bad_z_gates.contains(&gate.out)
+                && (2..circuit.num_input_bits).contains(&(bit as usize)))
+            .then_some((gate, bit))
+        })
+        .flat_map(|(gate, bit)| {
             let in1 = circuit.gates.iter().find(|g| g.out == gate.in1);
             let in2 = circuit.gates.iter().find(|g| g.out == gate.in2);
 
@@ -310,17 +183,13 @@ pub fn day_24_2() {
 
             let in1_is_input_xor = in1.is_some_and(|g| {
                 g.op == Operation::Xor
-                    && g.in1.starts_with('x')
-                    && &g.in1[1..] == gate_num
-                    && g.in2.starts_with('y')
-                    && &g.in2[1..] == gate_num
+                    && bus_bit(g.in1, 'x') == Some(bit)
+                    && bus_bit(g.in2, 'y') == Some(bit)
             });
             let in2_is_input_xor = in2.is_some_and(|g| {
                 g.op == Operation::Xor
-                    && g.in1.starts_with('x')
-                    && &g.in1[1..] == gate_num
-                    && g.in2.starts_with('y')
-                    && &g.in2[1..] == gate_num
+                    && bus_bit(g.in1, 'x') == Some(bit)
+                    && bus_bit(g.in2, 'y') == Some(bit)
             });
 
             let or_gate_name = if in1_is_input_xor {
@@ -406,40 +275,7 @@ pub fn day_24_2() {
     bad_gates.sort();
 
     if DEBUG {
-        let possible_pair_sets = get_pairs_from_set(bad_gates.clone());
-
-        possible_pair_sets.iter().for_each(|pair_set| {
-            let alternating_bits = (0..circuit.num_input_bits)
-                .step_by(2)
-                .fold(0, |acc, i| acc + (1 << i));
-
-            let passed = [
-                ((1 << circuit.num_input_bits) - 1, 1),
-                ((1 << (circuit.num_input_bits / 2)) - 1, 1),
-                (
-                    ((1 << (circuit.num_input_bits / 2)) - 1)
-                        << (circuit.num_input_bits.div_ceil(2)),
-                    1,
-                ),
-                (alternating_bits, 1),
-                (alternating_bits >> 1, 1),
-            ]
-            .iter()
-            .all(|&(x, y)| {
-                let mut swap_test = circuit.clone();
-                pair_set.iter().for_each(|pair| {
-                    swap_test.swap_gates(pair.0, pair.1);
-                });
-
-                swap_test.run_with_values(x, y);
-
-                swap_test.is_bits_ok()
-            });
-
-            if passed {
-                println!("valid pairs {:?}\n", pair_set);
-            }
-        });
+        search_swaps(&circuit, &bad_gates, bad_gates.len() / 2);
     }
 
     println!("{}", bad_gates.join(","));
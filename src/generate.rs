@@ -0,0 +1,202 @@
+/// Days that `generate` can write inputs for.
+pub const GENERATED_DAYS: [usize; 4] = [12, 16, 18, 20];
+
+/// Density used when none is given: the share of day 12 plots that copy the
+/// plant of a neighbour, the share of inner walls knocked out of a day 16
+/// maze, and the share of cells a day 18 byte falls on.
+pub fn default_density(day: usize) -> f64 {
+    match day {
+        12 => 0.8,
+        16 => 0.1,
+        _ => 0.5,
+    }
+}
+
+/// SplitMix64, so that the output only depends on the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        let unit = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+
+        unit < probability
+    }
+}
+
+/// A garden of plants `A` to `Z`. Each plot copies the plant above or to the
+/// left of it with the probability `density`, which grows regions of ragged
+/// shapes, with holes and enclosed regions.
+fn garden(rng: &mut Rng, size: usize, density: f64) -> String {
+    let mut map = vec![vec![b'A'; size]; size];
+
+    (0..size).for_each(|y| {
+        (0..size).for_each(|x| {
+            let neighbours = [
+                (y > 0).then(|| map[y - 1][x]),
+                (x > 0).then(|| map[y][x - 1]),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+            map[y][x] = if !neighbours.is_empty() && rng.chance(density) {
+                neighbours[rng.below(neighbours.len())]
+            } else {
+                b'A' + rng.below(26) as u8
+            };
+        });
+    });
+
+    to_text(&map)
+}
+
+type Room = (usize, usize);
+
+/// Carves a perfect maze into a `size`×`size` grid of walls, with rooms on
+/// odd coordinates. Returns the grid and, for each room, the room it was
+/// carved from.
+fn carve_maze(
+    rng: &mut Rng,
+    size: usize,
+) -> (Vec<Vec<u8>>, Vec<Vec<Option<Room>>>) {
+    let mut map = vec![vec![b'#'; size]; size];
+    let mut parent = vec![vec![None; size]; size];
+    let start = (1, size - 2);
+    let mut stack = vec![start];
+
+    map[start.1][start.0] = b'.';
+    parent[start.1][start.0] = Some(start);
+
+    while let Some(&(x, y)) = stack.last() {
+        let rooms = [
+            (x >= 3).then(|| (x - 2, y)),
+            (x + 3 < size).then_some((x + 2, y)),
+            (y >= 3).then(|| (x, y - 2)),
+            (y + 3 < size).then_some((x, y + 2)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|&(x2, y2)| map[y2][x2] == b'#')
+        .collect::<Vec<_>>();
+
+        if rooms.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let (x2, y2) = rooms[rng.below(rooms.len())];
+
+        map[(y + y2) / 2][(x + x2) / 2] = b'.';
+        map[y2][x2] = b'.';
+        parent[y2][x2] = Some((x, y));
+        stack.push((x2, y2));
+    }
+
+    (map, parent)
+}
+
+/// A reindeer maze with `S` in the bottom left and `E` in the top right
+/// corner. `density` is the share of inner walls knocked out to make loops.
+fn reindeer_maze(rng: &mut Rng, size: usize, density: f64) -> String {
+    let (mut map, _) = carve_maze(rng, size);
+
+    (1..size - 1).for_each(|y| {
+        (1..size - 1).for_each(|x| {
+            let is_inner_wall = map[y][x] == b'#'
+                && ((x % 2 == 0 && y % 2 == 1) || (x % 2 == 1 && y % 2 == 0));
+
+            if is_inner_wall && rng.chance(density) {
+                map[y][x] = b'.';
+            }
+        });
+    });
+
+    map[size - 2][1] = b'S';
+    map[1][size - 2] = b'E';
+
+    to_text(&map)
+}
+
+/// Distinct falling bytes on `density` of the cells, never on the start or
+/// the exit.
+fn falling_bytes(rng: &mut Rng, size: usize, density: f64) -> String {
+    let mut cells = (1..size * size - 1).collect::<Vec<_>>();
+    let num_bytes = ((cells.len() as f64 * density) as usize).min(cells.len());
+
+    // Partial Fisher-Yates shuffle.
+    (0..num_bytes).for_each(|i| {
+        let j = i + rng.below(cells.len() - i);
+        cells.swap(i, j);
+    });
+
+    cells
+        .iter()
+        .take(num_bytes)
+        .map(|cell| format!("{},{}\n", cell % size, cell / size))
+        .collect()
+}
+
+/// A race track with a single path, following the maze from the bottom left
+/// to the top right corner.
+fn race_track(rng: &mut Rng, size: usize) -> String {
+    let (_, parent) = carve_maze(rng, size);
+    let mut map = vec![vec![b'#'; size]; size];
+    let start = (1, size - 2);
+    let mut room = (size - 2, 1);
+
+    map[room.1][room.0] = b'E';
+
+    while room != start {
+        let Some(prev) = parent[room.1][room.0] else {
+            break;
+        };
+
+        map[(room.1 + prev.1) / 2][(room.0 + prev.0) / 2] = b'.';
+        map[prev.1][prev.0] = b'.';
+        room = prev;
+    }
+
+    map[start.1][start.0] = b'S';
+
+    to_text(&map)
+}
+
+fn to_text(map: &[Vec<u8>]) -> String {
+    map.iter()
+        .map(|row| String::from_utf8_lossy(row) + "\n")
+        .collect()
+}
+
+/// Returns a puzzle input for `day`, which is the same for the same seed.
+/// Maze sizes are rounded up to the next odd number.
+pub fn generate(
+    day: usize,
+    seed: u64,
+    size: usize,
+    density: f64,
+) -> Option<String> {
+    let mut rng = Rng(seed);
+    let maze_size = (size | 1).max(5);
+
+    match day {
+        12 => Some(garden(&mut rng, size.max(1), density)),
+        16 => Some(reindeer_maze(&mut rng, maze_size, density)),
+        18 => Some(falling_bytes(&mut rng, size.max(2), density)),
+        20 => Some(race_track(&mut rng, maze_size)),
+        _ => None,
+    }
+}
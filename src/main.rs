@@ -1,3 +1,4 @@
+mod asciicast;
 mod day_01_1;
 mod day_01_2;
 mod day_02_1;
@@ -8,6 +9,7 @@ mod day_04_1;
 mod day_04_2;
 mod day_05_1;
 mod day_05_2;
+mod day_06;
 mod day_06_1;
 mod day_06_2;
 mod day_07_1;
@@ -24,31 +26,39 @@ mod day_12_1;
 mod day_12_2;
 mod day_13_1;
 mod day_13_2;
+mod day_14;
 mod day_14_1;
 mod day_14_2;
+mod day_15;
 mod day_15_1;
 mod day_15_2;
+mod day_16;
 mod day_16_1;
 mod day_16_2;
 mod day_17_1;
 mod day_17_2;
+mod day_18;
 mod day_18_1;
 mod day_18_2;
 mod day_19_1;
 mod day_19_2;
+mod day_20;
 mod day_20_1;
 mod day_20_2;
+mod day_21;
 mod day_21_1;
 mod day_21_2;
 mod day_22_1;
 mod day_22_2;
 mod day_23_1;
 mod day_23_2;
+mod day_24;
 mod day_24_1;
 mod day_24_2;
 mod day_25_1;
 mod day_25_2;
 mod day_not_implemented;
+mod generate;
 
 use std::env;
 use std::{
@@ -57,23 +67,57 @@ use std::{
 };
 
 use crate::{
-    day_01_1::day_01_1, day_01_2::day_01_2, day_02_1::day_02_1,
-    day_02_2::day_02_2, day_03_1::day_03_1, day_03_2::day_03_2,
-    day_04_1::day_04_1, day_04_2::day_04_2, day_05_1::day_05_1,
-    day_05_2::day_05_2, day_06_1::day_06_1, day_06_2::day_06_2,
-    day_07_1::day_07_1, day_07_2::day_07_2, day_08_1::day_08_1,
-    day_08_2::day_08_2, day_09_1::day_09_1, day_09_2::day_09_2,
-    day_10_1::day_10_1, day_10_2::day_10_2, day_11_1::day_11_1,
-    day_11_2::day_11_2, day_12_1::day_12_1, day_12_2::day_12_2,
-    day_13_1::day_13_1, day_13_2::day_13_2, day_14_1::day_14_1,
-    day_14_2::day_14_2, day_15_1::day_15_1, day_15_2::day_15_2,
-    day_16_1::day_16_1, day_16_2::day_16_2, day_17_1::day_17_1,
-    day_17_2::day_17_2, day_18_1::day_18_1, day_18_2::day_18_2,
-    day_19_1::day_19_1, day_19_2::day_19_2, day_20_1::day_20_1,
-    day_20_2::day_20_2, day_21_1::day_21_1, day_21_2::day_21_2,
-    day_22_1::day_22_1, day_22_2::day_22_2, day_23_1::day_23_1,
-    day_23_2::day_23_2, day_24_1::day_24_1, day_24_2::day_24_2,
-    day_25_1::day_25_1, day_25_2::day_25_2,
+    day_01_1::day_01_1,
+    day_01_2::day_01_2,
+    day_02_1::day_02_1,
+    day_02_2::day_02_2,
+    day_03_1::day_03_1,
+    day_03_2::day_03_2,
+    day_04_1::day_04_1,
+    day_04_2::day_04_2,
+    day_05_1::day_05_1,
+    day_05_2::day_05_2,
+    day_06_1::day_06_1,
+    day_06_2::day_06_2,
+    day_07_1::day_07_1,
+    day_07_2::day_07_2,
+    day_08_1::day_08_1,
+    day_08_2::day_08_2,
+    day_09_1::day_09_1,
+    day_09_2::day_09_2,
+    day_10_1::day_10_1,
+    day_10_2::day_10_2,
+    day_11_1::day_11_1,
+    day_11_2::day_11_2,
+    day_12_1::day_12_1,
+    day_12_2::day_12_2,
+    day_13_1::day_13_1,
+    day_13_2::day_13_2,
+    day_14_1::day_14_1,
+    day_14_2::day_14_2,
+    day_15_1::day_15_1,
+    day_15_2::day_15_2,
+    day_16_1::day_16_1,
+    day_16_2::day_16_2,
+    day_17_1::day_17_1,
+    day_17_2::day_17_2,
+    day_18_1::day_18_1,
+    day_18_2::day_18_2,
+    day_19_1::day_19_1,
+    day_19_2::day_19_2,
+    day_20_1::day_20_1,
+    day_20_2::day_20_2,
+    day_21_1::day_21_1,
+    day_21_2::day_21_2,
+    day_22_1::day_22_1,
+    day_22_2::day_22_2,
+    day_23_1::day_23_1,
+    day_23_2::day_23_2,
+    day_24_1::day_24_1,
+    day_24_2::day_24_2,
+    day_25_1::day_25_1,
+    day_25_2::day_25_2,
+    generate::{default_density, generate, GENERATED_DAYS},
 };
 
 const DAYS: [[fn(); 2]; 25] = [
@@ -157,9 +201,43 @@ fn run_day(day: usize, part: usize) {
     println!();
 }
 
+/// Prints a generated input, without the banner so it can be redirected to a
+/// file.
+fn run_generate(args: &[String]) -> bool {
+    let day = args.first().and_then(|day| day.parse::<usize>().ok());
+    let seed = args.get(1).and_then(|seed| seed.parse::<u64>().ok());
+    let size = args.get(2).and_then(|size| size.parse::<usize>().ok());
+    let density = match args.get(3) {
+        Some(density) => density.parse::<f64>().ok(),
+        None => day.map(default_density),
+    };
+
+    match (day, seed, size, density) {
+        (Some(day), Some(seed), Some(size), Some(density))
+            if args.len() <= 4 && (0.0..=1.0).contains(&density) =>
+        {
+            match generate(day, seed, size, density) {
+                Some(input) => {
+                    print!("{}", input);
+                    true
+                }
+                None => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
 
+    if args.get(1).is_some_and(|command| command == "generate") {
+        if !run_generate(&args[2..]) {
+            println!("Expected `cargo run generate <day> <seed> <size> [<density>]`\n  <day> One of {:?}.\n  <seed> Any unsigned number, the same seed gives the same input.\n  <size> Side length of the grid.\n  <density> A value between 0 and 1: the share of plots copying a neighbour (day 12), of inner walls knocked out (day 16) or of cells corrupted (day 18).", GENERATED_DAYS);
+        }
+        return;
+    }
+
     println!("ADVENT OF CODE 2025\n");
 
     if args.len() > 1 {